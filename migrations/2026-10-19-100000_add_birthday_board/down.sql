@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN board_message_id;
+ALTER TABLE guild_settings DROP COLUMN board_channel_id;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN board_channel_id BIGINT;
+ALTER TABLE guild_settings ADD COLUMN board_message_id BIGINT;
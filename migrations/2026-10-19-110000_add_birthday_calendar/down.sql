@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN calendar_size;
+ALTER TABLE guild_settings DROP COLUMN calendar_message_id;
+ALTER TABLE guild_settings DROP COLUMN calendar_channel_id;
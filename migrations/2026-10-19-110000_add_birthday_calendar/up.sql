@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN calendar_channel_id BIGINT;
+ALTER TABLE guild_settings ADD COLUMN calendar_message_id BIGINT;
+ALTER TABLE guild_settings ADD COLUMN calendar_size INTEGER NOT NULL DEFAULT 10;
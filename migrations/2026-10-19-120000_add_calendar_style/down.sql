@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN calendar_font;
+ALTER TABLE guild_settings DROP COLUMN calendar_theme;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN calendar_theme TEXT NOT NULL DEFAULT 'Dark';
+ALTER TABLE guild_settings ADD COLUMN calendar_font TEXT NOT NULL DEFAULT 'Sans';
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN moderation_channel_id;
+ALTER TABLE guild_settings DROP COLUMN require_birthday_approval;
+ALTER TABLE birthdays DROP COLUMN approved;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE birthdays ADD COLUMN approved BOOLEAN NOT NULL DEFAULT 1;
+ALTER TABLE guild_settings ADD COLUMN require_birthday_approval BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE guild_settings ADD COLUMN moderation_channel_id BIGINT;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN birthday_changes_per_year;
+ALTER TABLE guild_settings DROP COLUMN birthday_change_cooldown_hours;
+DROP TABLE IF EXISTS birthday_changes;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE birthday_changes
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    user_id    BIGINT                            NOT NULL,
+    guild_id   BIGINT                            NOT NULL,
+    changed_at TIMESTAMP                         NOT NULL
+);
+
+CREATE INDEX birthday_changes_user_guild_index ON birthday_changes (user_id, guild_id);
+
+ALTER TABLE guild_settings ADD COLUMN birthday_change_cooldown_hours INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE guild_settings ADD COLUMN birthday_changes_per_year INTEGER NOT NULL DEFAULT 0;
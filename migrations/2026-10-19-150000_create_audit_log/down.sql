@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN audit_channel_id;
+DROP TABLE IF EXISTS audit_log;
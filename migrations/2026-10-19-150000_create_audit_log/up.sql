@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE audit_log
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id   BIGINT                            NOT NULL,
+    actor_id   BIGINT                            NOT NULL,
+    target_id  BIGINT                            NOT NULL,
+    action     TEXT                              NOT NULL,
+    old_value  TEXT,
+    new_value  TEXT,
+    created_at TIMESTAMP                         NOT NULL
+);
+
+CREATE INDEX audit_log_guild_index ON audit_log (guild_id, created_at);
+
+ALTER TABLE guild_settings ADD COLUMN audit_channel_id BIGINT;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN announcement_reactions;
+ALTER TABLE guild_settings DROP COLUMN thread_archive_minutes;
+ALTER TABLE guild_settings DROP COLUMN announcement_threads;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN announcement_threads BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE guild_settings ADD COLUMN thread_archive_minutes INTEGER NOT NULL DEFAULT 1440;
+ALTER TABLE guild_settings ADD COLUMN announcement_reactions TEXT;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN deliver_wishes_by_dm;
+DROP TABLE IF EXISTS birthday_wishes;
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE birthday_wishes
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id     BIGINT                            NOT NULL,
+    author_id    BIGINT                            NOT NULL,
+    recipient_id BIGINT                            NOT NULL,
+    message      TEXT                              NOT NULL,
+    created_at   TIMESTAMP                         NOT NULL,
+    delivered    BOOLEAN                           NOT NULL,
+    UNIQUE (guild_id, author_id, recipient_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN deliver_wishes_by_dm BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN anniversaries_announced_on;
+ALTER TABLE guild_settings DROP COLUMN anniversary_template;
+ALTER TABLE guild_settings DROP COLUMN anniversary_channel_id;
+DROP TABLE IF EXISTS anniversary_opt_outs;
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE anniversary_opt_outs
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    user_id  BIGINT                            NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    UNIQUE (user_id, guild_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN anniversary_channel_id BIGINT;
+ALTER TABLE guild_settings ADD COLUMN anniversary_template TEXT;
+ALTER TABLE guild_settings ADD COLUMN anniversaries_announced_on DATE;
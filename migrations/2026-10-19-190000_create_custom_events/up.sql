@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE custom_events
+(
+    id                INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id          BIGINT                            NOT NULL,
+    name              TEXT                              NOT NULL,
+    date              DATE                              NOT NULL,
+    recurrence        TEXT                              NOT NULL,
+    role_id           BIGINT,
+    created_by        BIGINT                            NOT NULL,
+    last_announced_on DATE
+);
+
+CREATE INDEX custom_events_guild_id ON custom_events (guild_id);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN cake_days_announced_on;
+ALTER TABLE guild_settings DROP COLUMN bot_cake_day;
+ALTER TABLE guild_settings DROP COLUMN guild_cake_day;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN guild_cake_day BOOLEAN NOT NULL DEFAULT 1;
+ALTER TABLE guild_settings ADD COLUMN bot_cake_day BOOLEAN NOT NULL DEFAULT 1;
+ALTER TABLE guild_settings ADD COLUMN cake_days_announced_on DATE;
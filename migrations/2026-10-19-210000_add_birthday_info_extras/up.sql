@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN birthday_info_extras BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN color_contrast_threshold;
+ALTER TABLE guild_settings DROP COLUMN color_contrast_policy;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN color_contrast_policy TEXT NOT NULL DEFAULT 'Warn';
+ALTER TABLE guild_settings ADD COLUMN color_contrast_threshold DOUBLE NOT NULL DEFAULT 2.0;
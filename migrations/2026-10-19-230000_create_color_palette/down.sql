@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN palette_only;
+DROP TABLE IF EXISTS color_palette;
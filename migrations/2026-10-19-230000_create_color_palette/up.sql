@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE color_palette
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    name     TEXT                              NOT NULL,
+    color    TEXT                              NOT NULL,
+    UNIQUE (guild_id, name)
+);
+
+ALTER TABLE guild_settings ADD COLUMN palette_only BOOLEAN NOT NULL DEFAULT 0;
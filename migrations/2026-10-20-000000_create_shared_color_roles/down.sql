@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN shared_color_roles;
+DROP TABLE IF EXISTS color_role_holders;
+DROP TABLE IF EXISTS color_roles;
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TABLE color_roles
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    role_id  BIGINT                            NOT NULL UNIQUE,
+    color    TEXT                              NOT NULL,
+    UNIQUE (guild_id, color)
+);
+
+CREATE TABLE color_role_holders
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    user_id  BIGINT                            NOT NULL,
+    role_id  BIGINT                            NOT NULL,
+    UNIQUE (guild_id, user_id)
+);
+
+CREATE INDEX color_role_holders_role_id ON color_role_holders (role_id);
+
+ALTER TABLE guild_settings ADD COLUMN shared_color_roles BOOLEAN NOT NULL DEFAULT 0;
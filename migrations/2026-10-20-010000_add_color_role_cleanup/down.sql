@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE guild_settings DROP COLUMN color_role_cleanup;
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE guild_settings ADD COLUMN color_role_anchor_id BIGINT;
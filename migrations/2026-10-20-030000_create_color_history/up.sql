@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE color_history
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id   BIGINT                            NOT NULL,
+    user_id    BIGINT                            NOT NULL,
+    color      TEXT                              NOT NULL,
+    changed_at TIMESTAMP                         NOT NULL
+);
+
+CREATE INDEX color_history_guild_id_user_id ON color_history (guild_id, user_id);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE birthdays ADD COLUMN pending_date DATE;
+
+UPDATE birthdays SET pending_date = date WHERE approved = 0;
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE birthday_announcements
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id     BIGINT                            NOT NULL,
+    user_id      BIGINT                            NOT NULL,
+    announced_at TIMESTAMP                         NOT NULL
+);
+
+CREATE INDEX birthday_announcements_guild_id ON birthday_announcements (guild_id);
@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE color_palette_old
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    name     TEXT                              NOT NULL,
+    color    TEXT                              NOT NULL,
+    UNIQUE (guild_id, name)
+);
+
+INSERT INTO color_palette_old (id, guild_id, name, color)
+SELECT id, guild_id, name, color
+FROM color_palette;
+
+DROP TABLE color_palette;
+ALTER TABLE color_palette_old RENAME TO color_palette;
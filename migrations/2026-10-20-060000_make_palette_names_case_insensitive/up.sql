@@ -0,0 +1,18 @@
+-- Your SQL goes here
+CREATE TABLE color_palette_new
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    name     TEXT COLLATE NOCASE               NOT NULL,
+    color    TEXT                              NOT NULL,
+    UNIQUE (guild_id, name)
+);
+
+-- Keep the newest entry of names that only differ in case.
+INSERT INTO color_palette_new (id, guild_id, name, color)
+SELECT id, guild_id, name, color
+FROM color_palette
+WHERE id IN (SELECT MAX(id) FROM color_palette GROUP BY guild_id, LOWER(name));
+
+DROP TABLE color_palette;
+ALTER TABLE color_palette_new RENAME TO color_palette;
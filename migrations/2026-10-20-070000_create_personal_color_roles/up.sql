@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE personal_color_roles
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    guild_id BIGINT                            NOT NULL,
+    user_id  BIGINT                            NOT NULL,
+    role_id  BIGINT                            NOT NULL UNIQUE,
+    UNIQUE (guild_id, user_id)
+);
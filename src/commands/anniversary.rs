@@ -0,0 +1,55 @@
+use crate::db::connection::establish_connection;
+use crate::db::queries::{delete_anniversary_opt_out, insert_anniversary_opt_out};
+use crate::utils::embed_utils::{create_anniversary_opt_embed, create_error_embed};
+use crate::{Context, Error};
+use poise::CreateReply;
+
+// Server join anniversary preferences
+#[poise::command(slash_command, subcommands("optout", "optin"), subcommand_required)]
+pub async fn anniversary(_: Context<'_>) -> Result<(), Error> {
+  Ok(())
+}
+
+/// Stops announcing your server join anniversaries.
+#[poise::command(slash_command)]
+async fn optout(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match insert_anniversary_opt_out(conn, i64::from(ctx.author().id), i64::from(guild_id)) {
+    Ok(_) => {
+      ctx.send(CreateReply::default().embed(create_anniversary_opt_embed(true)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving your preference: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Announces your server join anniversaries again.
+#[poise::command(slash_command)]
+async fn optin(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match delete_anniversary_opt_out(conn, i64::from(ctx.author().id), i64::from(guild_id)) {
+    Ok(_) => {
+      ctx.send(CreateReply::default().embed(create_anniversary_opt_embed(false)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving your preference: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::db::connection::establish_connection;
+use crate::db::queries::list_audit_entries;
+use crate::utils::audit_utils::format_audit_entry;
+use crate::utils::embed_utils::{create_empty_audit_embed, create_error_embed};
+use crate::utils::pagination_utils::paginate;
+use crate::{Context, Error};
+use poise::CreateReply;
+
+const AUDIT_PAGE_SIZE: usize = 10;
+const AUDIT_HISTORY_LIMIT: i64 = 200;
+
+// Browses the recorded moderator actions
+#[poise::command(
+  slash_command,
+  subcommands("recent"),
+  required_permissions = "VIEW_AUDIT_LOG",
+  subcommand_required
+)]
+pub async fn audit(_: Context<'_>) -> Result<(), Error> {
+  Ok(())
+}
+
+/// Shows the most recent birthday and color changes.
+#[poise::command(slash_command)]
+async fn recent(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let conn = &mut establish_connection();
+
+  match list_audit_entries(conn, i64::from(guild_id), AUDIT_HISTORY_LIMIT) {
+    Ok(entries) if entries.is_empty() => {
+      ctx.send(CreateReply::default().embed(create_empty_audit_embed()).ephemeral(true)).await?;
+    }
+    Ok(entries) => {
+      let pages = entries
+          .chunks(AUDIT_PAGE_SIZE)
+          .map(|chunk| chunk.iter().map(format_audit_entry).collect::<String>())
+          .collect::<Vec<String>>();
+
+      paginate(ctx, "📜 Audit Log", &pages).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting the audit log: {}", e),
+        "Please try again later.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
@@ -1,16 +1,26 @@
 use crate::db::connection::establish_connection;
-use crate::db::models::Birthday;
-use crate::db::queries::{delete_birthday, get_birthday, insert_birthday, list_birthdays};
-use crate::utils::birthday_utils::sort_birthdays_by_upcoming_date;
-use crate::utils::date_utils::{calculate_age, days_until_next_birthday, format_birthday_with_age, format_date};
-use crate::utils::embed_utils::{create_birthday_delete_embed, create_birthday_info_embed, create_birthday_set_embed, create_empty_birthday_embed, create_error_embed};
-use crate::utils::user_utils::{check_permission_for_member, get_user_id};
+use crate::db::models::{Birthday, NewBirthdayWish};
+use crate::db::queries::{delete_birthday, delete_birthday_wish, get_birthday, get_guild_settings, get_pending_birthday_wish, insert_birthday, list_birthdays, record_birthday_change, restore_birthday, submit_pending_birthday, upsert_birthday_wish};
+use crate::utils::audit_utils::{log_audit_event, AuditAction, AuditEvent};
+use crate::utils::birthday_utils::{birthday_announced_this_year, check_birthday_change_limits, create_birthday_list_pages, get_birthday_stats, refresh_birthday_views, request_birthday_approval, sort_birthdays_by_upcoming_date};
+use crate::utils::calendar_utils::{get_calendar_style, render_birthday_calendar};
+use crate::utils::date_utils::{days_in_month, days_until_next_birthday, format_date};
+use crate::utils::embed_utils::{create_birthday_delete_cancelled_embed, create_birthday_delete_confirm_embed, create_birthday_delete_embed, create_birthday_info_embed, create_birthday_month_embed, create_birthday_pending_embed, create_birthday_restored_embed, create_birthday_set_embed, create_birthday_stats_embed, create_birthday_wish_deleted_embed, create_birthday_wish_saved_embed, create_empty_birthday_embed, create_error_embed};
+use crate::utils::pagination_utils::paginate;
+use crate::utils::user_utils::{author_has_permission, check_permission_for_member, get_user_id};
 use crate::{Context, Error};
-use chrono::{NaiveDate, Utc};
-use poise::serenity_prelude::{Color, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, Member, Permissions};
+use chrono::{Datelike, NaiveDate, Utc};
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::{ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage, CreateAttachment, Member, Permissions, UserId};
 use poise::CreateReply;
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-#[poise::command(slash_command, subcommands("list", "set", "info", "delete"), subcommand_required)]
+const UNDO_WINDOW: Duration = Duration::from_secs(5 * 60);
+const WISH_WINDOW_DAYS: i64 = 30;
+
+#[poise::command(slash_command, subcommands("list", "set", "info", "delete", "calendar", "stats", "wish", "unwish"), subcommand_required)]
 pub async fn birthday(_: Context<'_>) -> Result<(), Error> {
   Ok(())
 }
@@ -26,7 +36,11 @@ async fn info(ctx: Context<'_>, member: Option<Member>) -> Result<(), Error> {
     Ok(Some(birthday)) => {
       let formatted_birthday = format_date(birthday.date);
       let days_until = days_until_next_birthday(birthday.date);
-      let embed = create_birthday_info_embed(formatted_birthday, days_until);
+      let show_extras = get_guild_settings(conn, i64::from(guild_id))
+          .ok()
+          .flatten()
+          .is_none_or(|settings| settings.birthday_info_extras);
+      let embed = create_birthday_info_embed(formatted_birthday, days_until, show_extras.then_some(birthday.date));
 
       ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
     }
@@ -65,21 +79,7 @@ async fn delete(ctx: Context<'_>, member: Option<Member>) -> Result<(), Error> {
 
   match get_birthday(conn, user_id, i64::from(guild_id)) {
     Ok(Some(birthday)) => {
-      match delete_birthday(conn, &birthday) {
-        Ok(_) => {
-          let embed = create_birthday_delete_embed(user_id);
-
-          ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
-        }
-        Err(e) => {
-          let error_embed = create_error_embed(
-            format!("Error while deleting the birthday: {}", e),
-            "Please try again later.".to_string(),
-          );
-
-          ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
-        }
-      }
+      delete_with_confirmation(ctx, conn, birthday).await?;
     }
     Ok(None) => {
       let error_embed = create_error_embed(
@@ -102,6 +102,132 @@ async fn delete(ctx: Context<'_>, member: Option<Member>) -> Result<(), Error> {
   Ok(())
 }
 
+/// Asks for confirmation before deleting, then offers an undo button for a few minutes.
+async fn delete_with_confirmation(
+  ctx: Context<'_>,
+  conn: &mut SqliteConnection,
+  birthday: Birthday,
+) -> Result<(), Error> {
+  let ctx_id = ctx.id();
+  let confirm_button_id = format!("{}confirm", ctx_id);
+  let cancel_button_id = format!("{}cancel", ctx_id);
+  let undo_button_id = format!("{}undo", ctx_id);
+
+  let reply = ctx.send(
+    CreateReply::default()
+        .embed(create_birthday_delete_confirm_embed(birthday.user_id))
+        .components(vec![CreateActionRow::Buttons(vec![
+          CreateButton::new(&confirm_button_id).label("Delete").style(ButtonStyle::Danger),
+          CreateButton::new(&cancel_button_id).label("Cancel").style(ButtonStyle::Secondary),
+        ])])
+        .ephemeral(true),
+  ).await?;
+
+  let press = ComponentInteractionCollector::new(ctx)
+      .author_id(ctx.author().id)
+      .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+      .timeout(Duration::from_secs(60))
+      .await;
+
+  let press = match press {
+    Some(press) if press.data.custom_id == confirm_button_id => press,
+    Some(press) => {
+      press.create_response(
+        ctx.serenity_context(),
+        CreateInteractionResponse::UpdateMessage(
+          CreateInteractionResponseMessage::new()
+              .embed(create_birthday_delete_cancelled_embed())
+              .components(vec![]),
+        ),
+      ).await?;
+      return Ok(());
+    }
+    None => {
+      reply.edit(ctx, CreateReply::default().embed(create_birthday_delete_cancelled_embed()).components(vec![])).await?;
+      return Ok(());
+    }
+  };
+
+  if let Err(e) = delete_birthday(conn, &birthday) {
+    let error_embed = create_error_embed(
+      format!("Error while deleting the birthday: {}", e),
+      "Please try again later.".to_string(),
+    );
+
+    press.create_response(
+      ctx.serenity_context(),
+      CreateInteractionResponse::UpdateMessage(
+        CreateInteractionResponseMessage::new().embed(error_embed).components(vec![]),
+      ),
+    ).await?;
+    return Ok(());
+  }
+
+  refresh_birthday_views(ctx.http(), conn, birthday.guild_id).await;
+  log_audit_event(ctx.http(), conn, AuditEvent {
+    guild_id: birthday.guild_id,
+    actor_id: u64::from(ctx.author().id) as i64,
+    target_id: birthday.user_id,
+    action: AuditAction::BirthdayDelete,
+    old_value: Some(birthday.date.to_string()),
+    new_value: None,
+  }).await;
+
+  press.create_response(
+    ctx.serenity_context(),
+    CreateInteractionResponse::UpdateMessage(
+      CreateInteractionResponseMessage::new()
+          .embed(create_birthday_delete_embed(birthday.user_id))
+          .components(vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(&undo_button_id).label("Undo").emoji('↩').style(ButtonStyle::Secondary),
+          ])]),
+    ),
+  ).await?;
+
+  let undo = ComponentInteractionCollector::new(ctx)
+      .author_id(ctx.author().id)
+      .filter(move |press| press.data.custom_id == undo_button_id)
+      .timeout(UNDO_WINDOW)
+      .await;
+
+  let Some(undo) = undo else {
+    reply.edit(ctx, CreateReply::default().embed(create_birthday_delete_embed(birthday.user_id)).components(vec![])).await?;
+    return Ok(());
+  };
+
+  let embed = match restore_birthday(conn, &birthday) {
+    Ok(true) => {
+      refresh_birthday_views(ctx.http(), conn, birthday.guild_id).await;
+      log_audit_event(ctx.http(), conn, AuditEvent {
+        guild_id: birthday.guild_id,
+        actor_id: u64::from(ctx.author().id) as i64,
+        target_id: birthday.user_id,
+        action: AuditAction::BirthdaySet,
+        old_value: None,
+        new_value: Some(birthday.date.to_string()),
+      }).await;
+      create_birthday_restored_embed(birthday.user_id)
+    }
+    Ok(false) => create_error_embed(
+      format!("A new birthday has already been set for <@{}>.", birthday.user_id),
+      "The previous birthday was not restored.".to_string(),
+    ),
+    Err(e) => create_error_embed(
+      format!("Error while restoring the birthday: {}", e),
+      "Please try again later.".to_string(),
+    ),
+  };
+
+  undo.create_response(
+    ctx.serenity_context(),
+    CreateInteractionResponse::UpdateMessage(
+      CreateInteractionResponseMessage::new().embed(embed).components(vec![]),
+    ),
+  ).await?;
+
+  Ok(())
+}
+
 /// Sets the birthday for a specified member (or your own if none is specified).
 #[poise::command(slash_command)]
 async fn set(ctx: Context<'_>, member: Option<Member>, #[description = "e.g, 1999-01-01"] date: String) -> Result<(), Error> {
@@ -115,9 +241,74 @@ async fn set(ctx: Context<'_>, member: Option<Member>, #[description = "e.g, 199
       }
 
       let conn = &mut establish_connection();
+      let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+      let is_moderator = author_has_permission(&ctx, Permissions::MANAGE_EVENTS).await;
+      let is_self_change = user_id == u64::from(ctx.author().id) as i64 && !is_moderator;
+      let needs_approval = settings.as_ref().is_some_and(|settings| settings.require_birthday_approval) && !is_moderator;
+      let now = Utc::now().naive_utc();
+
+      if is_self_change {
+        let refusal = match check_birthday_change_limits(conn, settings.as_ref(), user_id, i64::from(guild_id), now) {
+          Ok(None) => None,
+          Ok(Some(reason)) => Some(create_error_embed(reason, "Ask a moderator if you need to fix it sooner.".to_string())),
+          Err(e) => Some(create_error_embed(
+            format!("Error while checking birthday changes: {}", e),
+            "Please try again later".to_string(),
+          )),
+        };
 
-      match insert_birthday(conn, user_id, i64::from(guild_id), parsed_date) {
+        if let Some(error_embed) = refusal {
+          ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+          return Ok(());
+        }
+      }
+
+      let previous = get_birthday(conn, user_id, i64::from(guild_id)).ok().flatten();
+      // Members are announced once a year, however often they change or delete their birthday.
+      let announced = birthday_announced_this_year(conn, i64::from(guild_id), user_id, now).unwrap_or_else(|e| {
+        error!("Error checking birthday announcements for user {}: {:?}", user_id, e);
+        false
+      });
+      let result = if needs_approval {
+        submit_pending_birthday(conn, user_id, i64::from(guild_id), parsed_date)
+      } else {
+        insert_birthday(conn, user_id, i64::from(guild_id), parsed_date, announced)
+      };
+
+      if result.is_ok() {
+        let new_value = if needs_approval { format!("{} (pending)", parsed_date) } else { parsed_date.to_string() };
+
+        log_audit_event(ctx.http(), conn, AuditEvent {
+          guild_id: i64::from(guild_id),
+          actor_id: u64::from(ctx.author().id) as i64,
+          target_id: user_id,
+          action: AuditAction::BirthdaySet,
+          old_value: previous.map(|birthday| birthday.date.to_string()),
+          new_value: Some(new_value),
+        }).await;
+      }
+
+      if result.is_ok() && is_self_change
+          && let Err(e) = record_birthday_change(conn, user_id, i64::from(guild_id), now) {
+        error!("Error recording birthday change for user {}: {:?}", user_id, e);
+      }
+
+      match result {
+        Ok(_) if needs_approval => {
+          let moderation_channel_id = settings.and_then(|settings| settings.moderation_channel_id);
+          if let Err(e) = request_birthday_approval(ctx.http(), conn, moderation_channel_id, user_id, i64::from(guild_id)).await {
+            error!("Error requesting birthday approval in guild {}: {:?}", guild_id, e);
+          }
+
+          refresh_birthday_views(ctx.http(), conn, i64::from(guild_id)).await;
+
+          let embed = create_birthday_pending_embed(user_id, date);
+
+          ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+        }
         Ok(_) => {
+          refresh_birthday_views(ctx.http(), conn, i64::from(guild_id)).await;
+
           let embed = create_birthday_set_embed(user_id, date);
 
           ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
@@ -160,7 +351,7 @@ async fn list(ctx: Context<'_>) -> Result<(), Error> {
 
         let pages = create_birthday_list_pages(&birthdays);
 
-        paginate_birthday_list(ctx, &pages).await?;
+        paginate(ctx, "🎂 Birthday List", &pages).await?;
       }
     }
     Err(e) => {
@@ -175,82 +366,220 @@ async fn list(ctx: Context<'_>) -> Result<(), Error> {
   Ok(())
 }
 
-fn create_birthday_list_pages(birthdays: &[Birthday]) -> Vec<String> {
-  let page_size = 5;
-  birthdays
-      .chunks(page_size)
-      .map(|chunk| {
-        chunk.iter().map(|birthday| {
-          let formatted_birthday = format_birthday_with_age(birthday);
-          format!("<@{}>: {} ({} years old)\n", birthday.user_id, formatted_birthday, calculate_age(birthday.date))
-        }).collect::<String>()
-      })
-      .collect()
+/// Shows a calendar image with the birthdays of the given month (the current one by default).
+#[poise::command(slash_command)]
+async fn calendar(
+  ctx: Context<'_>,
+  #[description = "Month number, e.g. 12 for December"]
+  #[min = 1]
+  #[max = 12]
+  month: Option<u32>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let today = Utc::now().date_naive();
+  let month = month.unwrap_or(today.month());
+
+  ctx.defer_ephemeral().await?;
+
+  let conn = &mut establish_connection();
+  let birthdays = match list_birthdays(conn, i64::from(guild_id)) {
+    Ok(birthdays) => birthdays,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting the birthdays: {}", e),
+        "Please try again later.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  let last_day = days_in_month(today.year(), month);
+  let mut names_by_day: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+  let mut count = 0;
+
+  for birthday in birthdays.iter().filter(|birthday| birthday.date.month() == month) {
+    let Ok(member) = guild_id.member(ctx, UserId::new(birthday.user_id as u64)).await else {
+      continue;
+    };
+
+    names_by_day
+        .entry(birthday.date.day().min(last_day))
+        .or_default()
+        .push(member.display_name().to_string());
+    count += 1;
+  }
+
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let (theme, font) = get_calendar_style(settings.as_ref());
+
+  match render_birthday_calendar(today.year(), month, &names_by_day, theme, font) {
+    Ok(png) => {
+      let attachment_name = "birthday-calendar.png";
+      let month_name = NaiveDate::from_ymd_opt(today.year(), month, 1)
+          .expect("Month is validated by the command")
+          .format("%B")
+          .to_string();
+
+      ctx.send(
+        CreateReply::default()
+            .embed(create_birthday_month_embed(month_name, count, attachment_name))
+            .attachment(CreateAttachment::bytes(png, attachment_name))
+            .ephemeral(true),
+      ).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while rendering the calendar: {}", e),
+        "Please try again later.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Shows birthday statistics for the server.
+#[poise::command(slash_command)]
+async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let members = ctx.guild().map(|guild| guild.member_count);
+  let conn = &mut establish_connection();
+
+  match get_birthday_stats(conn, i64::from(guild_id), members) {
+    Ok(stats) => {
+      ctx.send(CreateReply::default().embed(create_birthday_stats_embed(&stats)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting birthday statistics: {}", e),
+        "Please try again later.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
 }
 
-async fn paginate_birthday_list(
+/// Leaves a private wish that will be delivered to the member on their birthday.
+#[poise::command(slash_command)]
+async fn wish(
   ctx: Context<'_>,
-  pages: &[String],
+  member: Member,
+  #[description = "Your birthday wish"]
+  #[max_length = 500]
+  message: String,
 ) -> Result<(), Error> {
-  let ctx_id = ctx.id();
-  let prev_button_id = format!("{}prev", ctx_id);
-  let next_button_id = format!("{}next", ctx_id);
-
-  let mut current_page = 0;
-  let total_pages = pages.len();
-  
-  let reply = {
-    let components = CreateActionRow::Buttons(vec![
-      CreateButton::new(&prev_button_id).emoji('◀'),
-      CreateButton::new(&next_button_id).emoji('▶'),
-    ]);
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let author_id = u64::from(ctx.author().id) as i64;
+  let recipient_id = u64::from(member.user.id) as i64;
 
-    CreateReply::default()
-        .embed(
-          CreateEmbed::new()
-              .title("🎂 Birthday List")
-              .description(&pages[0])
-              .color(Color::BLUE)
-              .footer(CreateEmbedFooter::new(format!("Page 1 of {}", total_pages)))
-              .timestamp(Utc::now())
-        )
-        .components(vec![components])
+  if author_id == recipient_id {
+    let error_embed = create_error_embed(
+      "You can't leave a birthday wish for yourself.".to_string(),
+      "Wish someone else a happy birthday!".to_string(),
+    );
+
+    ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  let conn = &mut establish_connection();
+
+  let birthday = match get_birthday(conn, recipient_id, i64::from(guild_id)) {
+    Ok(Some(birthday)) if birthday.approved => birthday,
+    Ok(_) => {
+      let error_embed = create_error_embed(
+        format!("No birthday set for <@{}>.", recipient_id),
+        "Wishes can only be left for members with a birthday set.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+    Err(e) => {
+      let error_embed = create_error_embed(
+        format!("Error while checking for birthday: {}", e),
+        "Please try again later".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+      return Ok(());
+    }
   };
 
-  ctx.send(reply.ephemeral(true)).await?;
+  let days_until = days_until_next_birthday(birthday.date);
+  if days_until == 0 || days_until > WISH_WINDOW_DAYS {
+    let error_embed = create_error_embed(
+      format!("Wishes can be left during the {} days before <@{}>'s birthday.", WISH_WINDOW_DAYS, recipient_id),
+      format!("Their birthday is in {} days.", days_until),
+    );
 
-  while let Some(press) = ComponentInteractionCollector::new(ctx)
-      .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-      .timeout(std::time::Duration::from_secs(3600 * 24))
-      .await
-  {
-    if press.data.custom_id == next_button_id {
-      current_page += 1;
-      if current_page >= total_pages {
-        current_page = 0;
-      }
-    } else if press.data.custom_id == prev_button_id {
-      current_page = current_page.checked_sub(1).unwrap_or(total_pages - 1);
-    } else {
-      continue;
+    ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  let updated = matches!(get_pending_birthday_wish(conn, i64::from(guild_id), author_id, recipient_id), Ok(Some(_)));
+  let new_wish = NewBirthdayWish {
+    guild_id: i64::from(guild_id),
+    author_id,
+    recipient_id,
+    message: &message,
+    created_at: Utc::now().naive_utc(),
+    delivered: false,
+  };
+
+  match upsert_birthday_wish(conn, &new_wish) {
+    Ok(_) => {
+      let embed = create_birthday_wish_saved_embed(member.user.id, updated, days_until);
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let error_embed = create_error_embed(
+        format!("Error while saving the wish: {}", e),
+        "Please try again later".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Removes the birthday wish you left for a member before it is delivered.
+#[poise::command(slash_command)]
+async fn unwish(ctx: Context<'_>, member: Member) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let author_id = u64::from(ctx.author().id) as i64;
+  let conn = &mut establish_connection();
+
+  match delete_birthday_wish(conn, i64::from(guild_id), author_id, u64::from(member.user.id) as i64) {
+    Ok(true) => {
+      ctx.send(CreateReply::default().embed(create_birthday_wish_deleted_embed(member.user.id)).ephemeral(true)).await?;
+    }
+    Ok(false) => {
+      let error_embed = create_error_embed(
+        format!("You have no pending wish for <@{}>.", member.user.id),
+        "Wishes can't be changed once delivered.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
     }
+    Err(e) => {
+      let error_embed = create_error_embed(
+        format!("Error while removing the wish: {}", e),
+        "Please try again later".to_string(),
+      );
 
-    press
-        .create_response(
-          ctx.serenity_context(),
-          CreateInteractionResponse::UpdateMessage(
-            CreateInteractionResponseMessage::new()
-                .embed(
-                  CreateEmbed::new()
-                      .title("🎂 Birthday List")
-                      .description(&pages[current_page])
-                      .color(Color::GOLD)
-                      .footer(CreateEmbedFooter::new(format!("Page {} of {}", current_page + 1, total_pages)))
-                      .timestamp(Utc::now())
-                ).ephemeral(true),
-          ),
-        )
-        .await?;
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    }
   }
 
   Ok(())
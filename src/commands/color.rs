@@ -1,15 +1,38 @@
-use crate::utils::color_utils::ColorUtils;
-use crate::utils::embed_utils::{create_color_created_embed, create_color_updated_embed, create_error_embed};
+use crate::db::connection::establish_connection;
+use crate::db::models::{GuildSettings, PaletteColor};
+use crate::db::queries::{delete_color_history_entries, forget_personal_color_role, get_color_role_holder, get_guild_settings, insert_color_history, list_color_history, list_palette_colors, track_personal_color_role};
+use crate::utils::audit_utils::{log_audit_event, AuditAction, AuditEvent};
+use crate::utils::avatar_utils::{avatar_png_url, fetch_dominant_colors};
+use crate::utils::color_role_utils::{assign_shared_color_role, delete_orphaned_color_roles, find_orphaned_color_roles, release_shared_color_role};
+use crate::utils::color_utils::{get_contrast_policy, ColorUtils, ContrastPolicy, ContrastReport, Rgb};
+use crate::utils::embed_utils::{create_avatar_colors_embed, create_color_cleanup_cancelled_embed, create_color_cleanup_confirm_embed, create_color_cleanup_done_embed, create_color_created_embed, create_color_history_embed, create_color_preview_embed, create_color_reset_embed, create_color_updated_embed, create_empty_color_history_embed, create_error_embed, create_no_orphaned_roles_embed, create_palette_picker_embed};
+use crate::utils::image_utils::render_color_preview;
+use crate::utils::pagination_utils::paginate_embeds;
 use crate::utils::user_utils::{check_permission_for_member, create_and_assign_user_specific_role, get_user_specific_role};
 use crate::{Context, Error};
-use poise::serenity_prelude::{Color, EditRole, Member, Permissions};
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::{AutocompleteChoice, ButtonStyle, ComponentInteractionCollector, ComponentInteractionDataKind, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, EditRole, GuildId, Member, Permissions, RoleId, UserId};
 use poise::CreateReply;
+use std::time::Duration;
 
-/// Sets your username color to the specified hex color (e.g., #ff5733).
+const COLOR_HISTORY_LIMIT: i64 = 25;
+const AVATAR_COLOR_COUNT: usize = 5;
+
+#[poise::command(slash_command, subcommands("set", "preview", "reset", "fromavatar", "history", "undo", "cleanup"), subcommand_required)]
+pub async fn color(_: Context<'_>) -> Result<(), Error> {
+  Ok(())
+}
+
+/// Sets your username color to a hex, rgb(), hsl() or CSS named color (e.g., #ff5733).
+///
+/// Leave the color empty to pick one from the server palette.
 #[poise::command(slash_command)]
-pub async fn color(
+async fn set(
   ctx: Context<'_>,
-  #[description = "e.g., #ff5733"] color: String,
+  #[description = "e.g., #ff5733, rgb(255, 87, 51), hsl(11, 100%, 60%) or teal"]
+  #[autocomplete = "autocomplete_palette_color"]
+  color: Option<String>,
   member: Option<Member>,
 ) -> Result<(), Error> {
   let target_user_id = if let Some(member) = &member {
@@ -22,67 +45,735 @@ pub async fn color(
     return Ok(());
   }
 
-  match ColorUtils::validate_hex_color(&color) {
-    Ok(_) => {
-      let cleaned_color = if color.starts_with('#') {
-        color.clone()
+  let guild_id = ctx
+      .guild_id()
+      .expect("Command can only be used in a guild.");
+
+  let conn = &mut establish_connection();
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let palette_only = settings.as_ref().is_some_and(|settings| settings.palette_only);
+  let palette = list_palette_colors(conn, i64::from(guild_id)).unwrap_or_default();
+
+  let color = match color {
+    Some(color) => color,
+    None => match pick_palette_color(ctx, &palette).await? {
+      Some(color) => color,
+      None => return Ok(()),
+    },
+  };
+
+  let Some(parsed_color) = resolve_or_report(ctx, &color, target_user_id, &palette, palette_only).await? else {
+    return Ok(());
+  };
+
+  apply_color(ctx, settings.as_ref(), guild_id, target_user_id, parsed_color).await?;
+
+  Ok(())
+}
+
+/// Shows how a color looks on Discord's themes before applying it.
+#[poise::command(slash_command)]
+async fn preview(
+  ctx: Context<'_>,
+  #[description = "e.g., #ff5733, rgb(255, 87, 51), hsl(11, 100%, 60%) or teal"]
+  #[autocomplete = "autocomplete_palette_color"]
+  color: String,
+  member: Option<Member>,
+) -> Result<(), Error> {
+  let target_member = match member.clone() {
+    Some(member) => member,
+    None => match ctx.author_member().await {
+      Some(member) => member.into_owned(),
+      None => return Ok(()),
+    },
+  };
+  let target_user_id = target_member.user.id;
+
+  if !check_permission_for_member(&ctx, member.as_ref(), Permissions::MANAGE_ROLES).await? {
+    return Ok(());
+  }
+
+  let guild_id = ctx
+      .guild_id()
+      .expect("Command can only be used in a guild.");
+
+  let conn = &mut establish_connection();
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let palette_only = settings.as_ref().is_some_and(|settings| settings.palette_only);
+  let palette = list_palette_colors(conn, i64::from(guild_id)).unwrap_or_default();
+
+  let Some(parsed_color) = resolve_or_report(ctx, &color, target_user_id, &palette, palette_only).await? else {
+    return Ok(());
+  };
+
+  // Preview what Apply would actually give the member.
+  let checked = check_contrast(settings.as_ref(), parsed_color);
+  let (preview_color, note) = match &checked {
+    Ok((color, note)) => (*color, note.clone()),
+    Err(reason) => (parsed_color, Some(reason.clone())),
+  };
+
+  let png = match render_color_preview(target_member.display_name(), preview_color) {
+    Ok(png) => png,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while rendering the preview: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  let attachment_name = "color-preview.png";
+  let embed = create_color_preview_embed(preview_color, target_user_id, ContrastReport::of(preview_color), note, checked.is_ok(), attachment_name);
+  let reply = CreateReply::default()
+      .embed(embed.clone())
+      .attachment(CreateAttachment::bytes(png, attachment_name))
+      .ephemeral(true);
+
+  if checked.is_err() {
+    ctx.send(reply).await?;
+    return Ok(());
+  }
+
+  let apply_button_id = format!("{}apply", ctx.id());
+  let reply = ctx.send(
+    reply.components(vec![CreateActionRow::Buttons(vec![
+      CreateButton::new(&apply_button_id).label("Apply").style(ButtonStyle::Success),
+    ])]),
+  ).await?;
+
+  let press = ComponentInteractionCollector::new(ctx)
+      .author_id(ctx.author().id)
+      .filter(move |press| press.data.custom_id == apply_button_id)
+      .timeout(Duration::from_secs(60))
+      .await;
+
+  let Some(press) = press else {
+    reply.edit(ctx, CreateReply::default().embed(embed).components(vec![])).await?;
+    return Ok(());
+  };
+
+  press.create_response(
+    ctx.serenity_context(),
+    CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().components(vec![])),
+  ).await?;
+
+  apply_color(ctx, settings.as_ref(), guild_id, target_user_id, parsed_color).await?;
+
+  Ok(())
+}
+
+/// Parses the color, telling the member what went wrong when it isn't valid or allowed.
+async fn resolve_or_report(
+  ctx: Context<'_>,
+  color: &str,
+  target_user_id: UserId,
+  palette: &[PaletteColor],
+  palette_only: bool,
+) -> Result<Option<Rgb>, Error> {
+  match ColorUtils::resolve_color(color, palette, palette_only) {
+    Ok(parsed_color) => Ok(Some(parsed_color)),
+    Err(e) => {
+      let footer = if palette_only {
+        "Run /color set without a color to pick one from the palette.".to_string()
       } else {
-        format!("#{}", color)
+        "Examples: #FF5733, #f53, rgb(255, 87, 51), hsl(11, 100%, 60%), teal".to_string()
       };
+      let embed = create_error_embed(
+        format!(
+          "<@{}> provided an invalid color **{}**: {}",
+          target_user_id, color, e
+        ), footer);
 
-      let (r, g, b) = match ColorUtils::hex_to_rgb(&cleaned_color) {
-        Some(rgb) => rgb,
-        None => {
-          let embed = create_error_embed(
-            format!("<@{}> provided an invalid color code.", target_user_id),
-            "Please use a valid hex color code like #ff5733.".to_string());
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      Ok(None)
+    }
+  }
+}
 
-          ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
-          return Ok(());
-        }
-      };
+/// Removes your custom color.
+#[poise::command(slash_command)]
+async fn reset(ctx: Context<'_>, member: Option<Member>) -> Result<(), Error> {
+  let target_user_id = if let Some(member) = &member {
+    member.user.id
+  } else {
+    ctx.author().id
+  };
+
+  if !check_permission_for_member(&ctx, member.as_ref(), Permissions::MANAGE_ROLES).await? {
+    return Ok(());
+  }
+
+  let guild_id = ctx
+      .guild_id()
+      .expect("Command can only be used in a guild.");
+
+  let conn = &mut establish_connection();
+  let personal_color = match get_user_specific_role(&ctx, guild_id, u64::from(target_user_id)).await? {
+    Some(role) => {
+      if let Err(e) = guild_id.delete_role(ctx, role.id).await {
+        let embed = create_error_embed(
+          format!("Error while removing the color role: {}", e),
+          "Make sure I can manage roles.".to_string());
+
+        ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+        return Ok(());
+      }
+
+      forget_deleted_color_role(conn, guild_id, role.id);
+      Some(Rgb::from(role.colour))
+    }
+    None => None,
+  };
+
+  let shared_color = match release_shared_color_role(ctx.http(), conn, guild_id, target_user_id).await {
+    Ok(shared_color) => shared_color,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while removing the color role: {}", e),
+        "Make sure I can manage roles.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  let Some(old_color) = personal_color.or(shared_color) else {
+    let embed = create_error_embed(
+      format!("<@{}> doesn't have a custom color.", target_user_id),
+      "Pick one with /color set.".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  };
+
+  log_color_change(ctx, guild_id, target_user_id, Some(old_color.to_hex()), None).await;
+
+  ctx.send(CreateReply::default().embed(create_color_reset_embed(old_color, target_user_id)).ephemeral(true)).await?;
+
+  Ok(())
+}
+
+/// Suggests colors picked from your avatar.
+#[poise::command(slash_command)]
+async fn fromavatar(ctx: Context<'_>, member: Option<Member>) -> Result<(), Error> {
+  let target_user = member.as_ref().map_or(ctx.author(), |member| &member.user).clone();
+
+  if !check_permission_for_member(&ctx, member.as_ref(), Permissions::MANAGE_ROLES).await? {
+    return Ok(());
+  }
+
+  let guild_id = ctx
+      .guild_id()
+      .expect("Command can only be used in a guild.");
+
+  let conn = &mut establish_connection();
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+
+  if settings.as_ref().is_some_and(|settings| settings.palette_only) {
+    let embed = create_error_embed(
+      "This server only allows colors from its palette.".to_string(),
+      "Run /color set without a color to pick one from the palette.".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  ctx.defer_ephemeral().await?;
+
+  let url = avatar_png_url(&target_user);
+  let colors = match fetch_dominant_colors(ctx.data().image_fetcher.as_ref(), &url, AVATAR_COLOR_COUNT).await {
+    Ok(colors) if !colors.is_empty() => colors,
+    Ok(_) => {
+      let embed = create_error_embed(
+        "The avatar has no visible colors.".to_string(),
+        "Pick one yourself with /color set.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while reading the avatar: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  let ctx_id = ctx.id();
+  let buttons = colors
+      .iter()
+      .enumerate()
+      .map(|(index, color)| CreateButton::new(format!("{}avatar{}", ctx_id, index)).label(color.to_hex()).style(ButtonStyle::Secondary))
+      .collect::<Vec<CreateButton>>();
+
+  let reply = ctx.send(
+    CreateReply::default()
+        .embed(create_avatar_colors_embed(target_user.id, &colors))
+        .components(vec![CreateActionRow::Buttons(buttons)])
+        .ephemeral(true),
+  ).await?;
+
+  let press = ComponentInteractionCollector::new(ctx)
+      .author_id(ctx.author().id)
+      .filter(move |press| press.data.custom_id.starts_with(&format!("{}avatar", ctx_id)))
+      .timeout(Duration::from_secs(60))
+      .await;
+
+  let Some(press) = press else {
+    reply.edit(ctx, CreateReply::default().embed(create_avatar_colors_embed(target_user.id, &colors)).components(vec![])).await?;
+    return Ok(());
+  };
+
+  let picked = press.data.custom_id
+      .strip_prefix(&format!("{}avatar", ctx_id))
+      .and_then(|index| index.parse::<usize>().ok())
+      .and_then(|index| colors.get(index).copied());
+
+  press.create_response(
+    ctx.serenity_context(),
+    CreateInteractionResponse::UpdateMessage(
+      CreateInteractionResponseMessage::new()
+          .content(format!("Picked **{}**.", picked.map_or("-".to_string(), |color| color.to_hex())))
+          .embeds(vec![])
+          .components(vec![]),
+    ),
+  ).await?;
+
+  if let Some(picked) = picked {
+    apply_color(ctx, settings.as_ref(), guild_id, target_user.id, picked).await?;
+  }
+
+  Ok(())
+}
+
+/// Shows the colors you had before.
+#[poise::command(slash_command)]
+async fn history(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Command can only be used in a guild.");
+  let conn = &mut establish_connection();
+
+  match list_color_history(conn, i64::from(guild_id), i64::from(ctx.author().id), COLOR_HISTORY_LIMIT) {
+    Ok(history) if history.is_empty() => {
+      ctx.send(CreateReply::default().embed(create_empty_color_history_embed()).ephemeral(true)).await?;
+    }
+    Ok(history) => {
+      let embeds = history
+          .iter()
+          .enumerate()
+          .filter_map(|(steps_back, entry)| {
+            let color = ColorUtils::hex_to_rgb(&entry.color)?;
+            Some(create_color_history_embed(color, entry.changed_at.and_utc().timestamp(), steps_back))
+          })
+          .collect::<Vec<CreateEmbed>>();
+
+      paginate_embeds(ctx, &embeds).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting your color history: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Goes back to one of your previous colors.
+#[poise::command(slash_command)]
+async fn undo(
+  ctx: Context<'_>,
+  #[description = "How many changes to go back (default 1)"]
+  #[min = 1]
+  #[max = 24]
+  steps: Option<u8>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Command can only be used in a guild.");
+  let user_id = ctx.author().id;
+  let steps = steps.unwrap_or(1) as usize;
+
+  let conn = &mut establish_connection();
+  let history = match list_color_history(conn, i64::from(guild_id), i64::from(user_id), steps as i64 + 1) {
+    Ok(history) => history,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting your color history: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  // After a reset the latest entry is the color that was removed, so going back one step restores it.
+  let has_color = get_user_specific_role(&ctx, guild_id, u64::from(user_id)).await?.is_some()
+      || get_color_role_holder(conn, i64::from(guild_id), i64::from(user_id)).ok().flatten().is_some();
+  let target = if has_color { steps } else { steps - 1 };
+
+  let Some(previous) = history.get(target) else {
+    let embed = create_error_embed(
+      "You don't have that many earlier colors.".to_string(),
+      "See them with /color history.".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  };
+
+  // The palette may have changed since the color was picked.
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let palette_only = settings.as_ref().is_some_and(|settings| settings.palette_only);
+  let palette = list_palette_colors(conn, i64::from(guild_id)).unwrap_or_default();
+
+  let parsed_color = match ColorUtils::resolve_color(&previous.color, &palette, palette_only) {
+    Ok(parsed_color) => parsed_color,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Can't go back to **{}**: {}", previous.color, e),
+        "Run /color set without a color to pick one from the palette.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  if apply_color(ctx, settings.as_ref(), guild_id, user_id, parsed_color).await?.is_some() {
+    // The color was recorded again as the latest entry, so drop the ones it stepped back over.
+    let ids = history.iter().take(target + 1).map(|entry| entry.id).collect::<Vec<i32>>();
+
+    if let Err(e) = delete_color_history_entries(conn, &ids) {
+      error!("Error trimming color history for user {} in guild {}: {:?}", user_id, guild_id, e);
+    }
+  }
+
+  Ok(())
+}
+
+/// Stops tracking a personal color role that was just deleted.
+fn forget_deleted_color_role(conn: &mut SqliteConnection, guild_id: GuildId, role_id: RoleId) {
+  if let Err(e) = forget_personal_color_role(conn, i64::from(role_id)) {
+    error!("Error forgetting color role {} in guild {}: {:?}", role_id, guild_id, e);
+  }
+}
+
+/// Runs the color through the guild's contrast policy.
+///
+/// Returns the color to use with a note for the member, or why the color is rejected.
+fn check_contrast(settings: Option<&GuildSettings>, color: Rgb) -> Result<(Rgb, Option<String>), String> {
+  let (policy, threshold) = get_contrast_policy(settings);
+  let requested = ContrastReport::of(color);
+
+  // Adjusting a palette color would hand out a color that isn't in the palette.
+  let policy = match policy {
+    ContrastPolicy::Adjust if settings.is_some_and(|settings| settings.palette_only) => ContrastPolicy::Warn,
+    policy => policy,
+  };
+
+  match policy {
+    _ if requested.is_readable(threshold) => Ok((color, None)),
+    ContrastPolicy::Off => Ok((color, None)),
+    ContrastPolicy::Warn => Ok((color, Some(format!(
+      "⚠ **{}** is hard to read on one of Discord's themes (below {:.1}:1).",
+      color, threshold
+    )))),
+    ContrastPolicy::Adjust => {
+      let adjusted = ColorUtils::adjust_for_contrast(color, threshold);
+      Ok((adjusted, Some(format!("Adjusted from **{}** so it stays readable on both themes.", color))))
+    }
+    ContrastPolicy::Reject => Err(format!(
+      "**{}** is too hard to read on Discord's themes (dark {:.2}:1, light {:.2}:1, minimum {:.1}:1).",
+      color, requested.dark, requested.light, threshold
+    )),
+  }
+}
+
+/// Applies the guild's contrast policy, then updates or creates the member's color role.
+///
+/// Returns the color that was applied, or `None` when the member was shown an error instead.
+async fn apply_color(
+  ctx: Context<'_>,
+  settings: Option<&GuildSettings>,
+  guild_id: GuildId,
+  target_user_id: UserId,
+  parsed_color: Rgb,
+) -> Result<Option<Rgb>, Error> {
+  let (parsed_color, note) = match check_contrast(settings, parsed_color) {
+    Ok(checked) => checked,
+    Err(reason) => {
+      let embed = create_error_embed(reason, "Try a lighter or more saturated color.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(None);
+    }
+  };
+  let contrast = ContrastReport::of(parsed_color);
+  let anchor_id = settings
+      .and_then(|settings| settings.color_role_anchor_id)
+      .map(|role_id| RoleId::new(role_id as u64));
+  let conn = &mut establish_connection();
 
-      let guild_id = ctx
-          .guild_id()
-          .expect("Command can only be used in a guild.");
+  if settings.is_some_and(|settings| settings.shared_color_roles) {
+    let personal_role = get_user_specific_role(&ctx, guild_id, u64::from(target_user_id)).await?;
 
-      match get_user_specific_role(&ctx, guild_id, u64::from(target_user_id)).await? {
-        Some(mut role) => {
-          if let Err(e) = role.edit(ctx, EditRole::default().colour(Color::from_rgb(r, g, b))).await {
-            return Err(e.into());
+    match assign_shared_color_role(ctx.http(), conn, guild_id, target_user_id, parsed_color, anchor_id).await {
+      Ok(previous_color) => {
+        // A personal role left from before the switch would hide the shared color.
+        let personal_color = match personal_role {
+          Some(role) => {
+            match guild_id.delete_role(ctx, role.id).await {
+              Ok(()) => forget_deleted_color_role(conn, guild_id, role.id),
+              Err(e) => error!("Error deleting personal color role {} in guild {}: {:?}", role.id, guild_id, e),
+            }
+            Some(Rgb::from(role.colour))
           }
+          None => None,
+        };
+        let old_color = previous_color.or(personal_color).map(|color| color.to_hex());
+        log_color_change(ctx, guild_id, target_user_id, old_color, Some(&parsed_color.to_hex())).await;
 
-          ctx.send(
-            CreateReply::default()
-                .embed(create_color_updated_embed(
-                  cleaned_color,
-                  r, g, b,
-                  target_user_id,
-                )).ephemeral(true)).await?;
-        }
-        None => {
-          create_and_assign_user_specific_role(ctx, guild_id, target_user_id, r, g, b).await?;
-
-          ctx.send(
-            CreateReply::default()
-                .embed(create_color_created_embed(
-                  cleaned_color,
-                  r, g, b,
-                  target_user_id,
-                )).ephemeral(true)).await?;
-        }
+        ctx.send(
+          CreateReply::default()
+              .embed(create_color_updated_embed(parsed_color, target_user_id, contrast, note))
+              .ephemeral(true)).await?;
+
+        return Ok(Some(parsed_color));
+      }
+      Err(e) => {
+        let embed = create_error_embed(
+          format!("Error while assigning the color role: {}", e),
+          "Make sure I can manage roles.".to_string());
+
+        ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+        return Ok(None);
+      }
+    }
+  }
+
+  let shared_color = match release_shared_color_role(ctx.http(), conn, guild_id, target_user_id).await {
+    Ok(shared_color) => shared_color,
+    Err(e) => {
+      error!("Error releasing shared color role for user {} in guild {}: {:?}", target_user_id, guild_id, e);
+      None
+    }
+  };
+
+  match get_user_specific_role(&ctx, guild_id, u64::from(target_user_id)).await? {
+    Some(mut role) => {
+      let old_color = Rgb::from(role.colour);
+
+      if let Err(e) = role.edit(ctx, EditRole::default().colour(parsed_color)).await {
+        return Err(e.into());
+      }
+
+      // Roles created before they were tracked are picked up here.
+      if let Err(e) = track_personal_color_role(conn, i64::from(guild_id), i64::from(target_user_id), i64::from(role.id)) {
+        error!("Error tracking color role {} in guild {}: {:?}", role.id, guild_id, e);
       }
+
+      log_color_change(ctx, guild_id, target_user_id, Some(old_color.to_hex()), Some(&parsed_color.to_hex())).await;
+
+      ctx.send(
+        CreateReply::default()
+            .embed(create_color_updated_embed(parsed_color, target_user_id, contrast, note))
+            .ephemeral(true)).await?;
+    }
+    None => {
+      create_and_assign_user_specific_role(ctx, conn, guild_id, target_user_id, parsed_color, anchor_id).await?;
+
+      log_color_change(ctx, guild_id, target_user_id, shared_color.map(|color| color.to_hex()), Some(&parsed_color.to_hex())).await;
+
+      ctx.send(
+        CreateReply::default()
+            .embed(create_color_created_embed(parsed_color, target_user_id, contrast, note))
+            .ephemeral(true)).await?;
     }
-    Err(_) => {
+  }
+
+  Ok(Some(parsed_color))
+}
+
+/// Lists color roles whose member left or that nobody holds, and deletes them after confirmation.
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+async fn cleanup(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Command can only be used in a guild.");
+  ctx.defer_ephemeral().await?;
+
+  let conn = &mut establish_connection();
+  let orphans = match find_orphaned_color_roles(ctx.http(), conn, guild_id).await {
+    Ok(orphans) => orphans,
+    Err(e) => {
       let embed = create_error_embed(
-        format!(
-          "<@{}> provided an invalid color code **{}**.",
-          target_user_id, color
-        ), "Example: #FF5733".to_string());
+        format!("Error while looking for orphaned roles: {}", e),
+        "Please try again later.".to_string());
 
       ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
     }
+  };
+
+  if orphans.is_empty() {
+    ctx.send(CreateReply::default().embed(create_no_orphaned_roles_embed()).ephemeral(true)).await?;
+    return Ok(());
   }
 
+  let ctx_id = ctx.id();
+  let confirm_button_id = format!("{}confirm", ctx_id);
+  let cancel_button_id = format!("{}cancel", ctx_id);
+
+  let reply = ctx.send(
+    CreateReply::default()
+        .embed(create_color_cleanup_confirm_embed(&orphans))
+        .components(vec![CreateActionRow::Buttons(vec![
+          CreateButton::new(&confirm_button_id).label("Delete").style(ButtonStyle::Danger),
+          CreateButton::new(&cancel_button_id).label("Cancel").style(ButtonStyle::Secondary),
+        ])])
+        .ephemeral(true),
+  ).await?;
+
+  let press = ComponentInteractionCollector::new(ctx)
+      .author_id(ctx.author().id)
+      .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+      .timeout(Duration::from_secs(60))
+      .await;
+
+  let press = match press {
+    Some(press) if press.data.custom_id == confirm_button_id => press,
+    Some(press) => {
+      press.create_response(
+        ctx.serenity_context(),
+        CreateInteractionResponse::UpdateMessage(
+          CreateInteractionResponseMessage::new()
+              .embed(create_color_cleanup_cancelled_embed())
+              .components(vec![]),
+        ),
+      ).await?;
+      return Ok(());
+    }
+    None => {
+      reply.edit(ctx, CreateReply::default().embed(create_color_cleanup_cancelled_embed()).components(vec![])).await?;
+      return Ok(());
+    }
+  };
+
+  // Deleting many roles can take longer than the 3 seconds Discord waits for a response.
+  press.create_response(ctx.serenity_context(), CreateInteractionResponse::Acknowledge).await?;
+  let deleted = delete_orphaned_color_roles(ctx.http(), conn, guild_id, &orphans).await;
+
+  reply.edit(ctx, CreateReply::default().embed(create_color_cleanup_done_embed(deleted, orphans.len())).components(vec![])).await?;
+
   Ok(())
 }
+
+/// Offers the palette in a select menu and returns the picked entry's name.
+async fn pick_palette_color(ctx: Context<'_>, palette: &[PaletteColor]) -> Result<Option<String>, Error> {
+  if palette.is_empty() {
+    let embed = create_error_embed(
+      "Please provide a color.".to_string(),
+      "Example: #FF5733".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(None);
+  }
+
+  let menu_id = format!("{}palette", ctx.id());
+  // Select menus hold at most 25 options; the rest stay reachable by typing the name.
+  let options = palette
+      .iter()
+      .take(25)
+      .map(|entry| CreateSelectMenuOption::new(&entry.name, &entry.name).description(&entry.color))
+      .collect::<Vec<CreateSelectMenuOption>>();
+
+  let reply = ctx.send(
+    CreateReply::default()
+        .embed(create_palette_picker_embed())
+        .components(vec![CreateActionRow::SelectMenu(
+          CreateSelectMenu::new(&menu_id, CreateSelectMenuKind::String { options }).placeholder("Choose a color"),
+        )])
+        .ephemeral(true),
+  ).await?;
+
+  let selection = ComponentInteractionCollector::new(ctx)
+      .author_id(ctx.author().id)
+      .filter(move |selection| selection.data.custom_id == menu_id)
+      .timeout(Duration::from_secs(60))
+      .await;
+
+  let Some(selection) = selection else {
+    reply.edit(ctx, CreateReply::default().embed(create_palette_picker_embed()).components(vec![])).await?;
+    return Ok(None);
+  };
+
+  let picked = match &selection.data.kind {
+    ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+    _ => None,
+  };
+
+  selection.create_response(
+    ctx.serenity_context(),
+    CreateInteractionResponse::UpdateMessage(
+      CreateInteractionResponseMessage::new()
+          .content(format!("Picked **{}**.", picked.as_deref().unwrap_or("-")))
+          .embeds(vec![])
+          .components(vec![]),
+    ),
+  ).await?;
+
+  Ok(picked)
+}
+
+pub async fn autocomplete_palette_color(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+  let Some(guild_id) = ctx.guild_id() else {
+    return Vec::new();
+  };
+
+  let partial = partial.trim().to_lowercase();
+  let palette = list_palette_colors(&mut establish_connection(), i64::from(guild_id)).unwrap_or_default();
+
+  palette
+      .into_iter()
+      .filter(|entry| entry.name.to_lowercase().contains(&partial) || entry.color.contains(&partial))
+      .take(25)
+      .map(|entry| AutocompleteChoice::new(format!("{} ({})", entry.name, entry.color), entry.name))
+      .collect()
+}
+
+/// Writes the change to the audit log and the member's color history.
+///
+/// A reset (no new color) only goes to the audit log.
+async fn log_color_change(ctx: Context<'_>, guild_id: GuildId, target_user_id: UserId, old_color: Option<String>, new_color: Option<&str>) {
+  let conn = &mut establish_connection();
+
+  if let Some(new_color) = new_color {
+    // Members who had a color before history was kept start it with that color, so they can undo the change.
+    let seed = match &old_color {
+      Some(old_color) => list_color_history(conn, i64::from(guild_id), i64::from(target_user_id), 1)
+          .map(|history| history.is_empty().then_some(old_color.as_str())),
+      None => Ok(None),
+    };
+
+    let saved = seed.and_then(|seed| {
+      if let Some(seed) = seed {
+        insert_color_history(conn, i64::from(guild_id), i64::from(target_user_id), seed)?;
+      }
+      insert_color_history(conn, i64::from(guild_id), i64::from(target_user_id), new_color)
+    });
+
+    if let Err(e) = saved {
+      error!("Error saving color history for user {} in guild {}: {:?}", target_user_id, guild_id, e);
+    }
+  }
+
+  log_audit_event(ctx.http(), conn, AuditEvent {
+    guild_id: i64::from(guild_id),
+    actor_id: u64::from(ctx.author().id) as i64,
+    target_id: u64::from(target_user_id) as i64,
+    action: AuditAction::ColorChange,
+    old_value: old_color,
+    new_value: new_color.map(str::to_string),
+  }).await;
+}
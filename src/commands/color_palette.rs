@@ -0,0 +1,126 @@
+use crate::commands::color::autocomplete_palette_color;
+use crate::db::connection::establish_connection;
+use crate::db::queries::{delete_palette_color, list_palette_colors, upsert_palette_color};
+use crate::utils::color_utils::ColorUtils;
+use crate::utils::embed_utils::{create_empty_palette_embed, create_error_embed, create_palette_color_added_embed, create_palette_color_removed_embed};
+use crate::utils::pagination_utils::paginate;
+use crate::{Context, Error};
+use poise::CreateReply;
+
+const PALETTE_PAGE_SIZE: usize = 15;
+
+// Manages the colors members can pick with /color set
+#[poise::command(
+  slash_command,
+  subcommands("add", "remove", "list"),
+  required_permissions = "MANAGE_GUILD",
+  subcommand_required
+)]
+pub async fn colorpalette(_: Context<'_>) -> Result<(), Error> {
+  Ok(())
+}
+
+/// Adds a color to the palette, or changes the color of an existing entry.
+#[poise::command(slash_command)]
+async fn add(
+  ctx: Context<'_>,
+  #[description = "e.g., Ocean"]
+  #[max_length = 32]
+  name: String,
+  #[description = "e.g., #1e90ff, rgb(30, 144, 255) or dodgerblue"] color: String,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let name = name.trim();
+
+  if name.is_empty() {
+    let embed = create_error_embed(
+      "The palette color needs a name.".to_string(),
+      "Example: Ocean".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  let parsed_color = match ColorUtils::parse_color(&color) {
+    Ok(parsed_color) => parsed_color,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Invalid color **{}**: {}", color, e),
+        "Example: #FF5733".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  let conn = &mut establish_connection();
+
+  match upsert_palette_color(conn, i64::from(guild_id), name, &parsed_color.to_hex()) {
+    Ok(_) => {
+      ctx.send(CreateReply::default().embed(create_palette_color_added_embed(name, parsed_color)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving the palette color: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Removes a color from the palette.
+#[poise::command(slash_command)]
+async fn remove(
+  ctx: Context<'_>,
+  #[autocomplete = "autocomplete_palette_color"] name: String,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let embed = match delete_palette_color(conn, i64::from(guild_id), name.trim()) {
+    Ok(true) => create_palette_color_removed_embed(name.trim()),
+    Ok(false) => create_error_embed(
+      format!("No palette color named **{}**.", name),
+      "See the palette with /colorpalette list.".to_string()),
+    Err(e) => create_error_embed(
+      format!("Error while removing the palette color: {}", e),
+      "Please try again later.".to_string()),
+  };
+
+  ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+
+  Ok(())
+}
+
+/// Lists the palette colors.
+#[poise::command(slash_command)]
+async fn list(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match list_palette_colors(conn, i64::from(guild_id)) {
+    Ok(palette) if palette.is_empty() => {
+      ctx.send(CreateReply::default().embed(create_empty_palette_embed()).ephemeral(true)).await?;
+    }
+    Ok(palette) => {
+      let pages = palette
+          .chunks(PALETTE_PAGE_SIZE)
+          .map(|chunk| chunk.iter().map(|entry| format!("`{}` **{}**\n", entry.color, entry.name)).collect::<String>())
+          .collect::<Vec<String>>();
+
+      paginate(ctx, "🎨 Color Palette", &pages).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting the palette: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
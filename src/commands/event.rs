@@ -0,0 +1,128 @@
+use crate::db::connection::establish_connection;
+use crate::db::models::NewCustomEvent;
+use crate::db::queries::{delete_custom_event, insert_custom_event, list_custom_events};
+use crate::utils::date_utils::format_date;
+use crate::utils::embed_utils::{create_custom_event_created_embed, create_custom_event_deleted_embed, create_empty_custom_events_embed, create_error_embed};
+use crate::utils::event_utils::{create_custom_event_pages, Recurrence};
+use crate::utils::pagination_utils::paginate;
+use crate::{Context, Error};
+use chrono::{Local, NaiveDate};
+use poise::serenity_prelude::Role;
+use poise::{ChoiceParameter, CreateReply};
+
+// Recurring server events announced alongside birthdays
+#[poise::command(slash_command, subcommands("create", "list", "delete"), subcommand_required)]
+pub async fn event(_: Context<'_>) -> Result<(), Error> {
+  Ok(())
+}
+
+/// Creates an event that is announced on its date and every time it comes around again.
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+async fn create(
+  ctx: Context<'_>,
+  #[max_length = 100] name: String,
+  #[description = "First occurrence, e.g. 2020-05-17"] date: String,
+  recurrence: Recurrence,
+  #[description = "Role to ping when the event is announced"] role: Option<Role>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let name = name.trim();
+
+  if name.is_empty() {
+    let error_embed = create_error_embed(
+      "The event name can't be empty.".to_string(),
+      "Example: Server Birthday".to_string(),
+    );
+
+    ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  let Ok(parsed_date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+    let error_embed = create_error_embed(
+      "Invalid date format. Please use YYYY-MM-DD.".to_string(),
+      "Example: 2020-05-17".to_string(),
+    );
+
+    ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    return Ok(());
+  };
+
+  let conn = &mut establish_connection();
+  let event = NewCustomEvent {
+    guild_id: i64::from(guild_id),
+    name,
+    date: parsed_date,
+    recurrence: recurrence.name(),
+    role_id: role.map(|role| i64::from(role.id)),
+    created_by: i64::from(ctx.author().id),
+  };
+
+  match insert_custom_event(conn, &event) {
+    Ok(_) => {
+      let next = recurrence.next_occurrence(parsed_date, Local::now().naive_utc().date());
+      let embed = create_custom_event_created_embed(event.name, recurrence.name(), format_date(next));
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let error_embed = create_error_embed(
+        format!("Error while creating the event: {}", e),
+        "Please try again later.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Lists this server's custom events with their next date.
+#[poise::command(slash_command)]
+async fn list(ctx: Context<'_>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let conn = &mut establish_connection();
+
+  match list_custom_events(conn, i64::from(guild_id)) {
+    Ok(events) if events.is_empty() => {
+      ctx.send(CreateReply::default().embed(create_empty_custom_events_embed()).ephemeral(true)).await?;
+    }
+    Ok(events) => {
+      paginate(ctx, "📣 Server Events", &create_custom_event_pages(&events)).await?;
+    }
+    Err(e) => {
+      let error_embed = create_error_embed(
+        format!("Error while getting the events: {}", e),
+        "Please try again later.".to_string(),
+      );
+
+      ctx.send(CreateReply::default().embed(error_embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Deletes a custom event by the number shown in /event list.
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+async fn delete(ctx: Context<'_>, #[description = "Event number, e.g. 3"] id: i32) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().expect("Guild ID is required");
+  let conn = &mut establish_connection();
+
+  let embed = match delete_custom_event(conn, i64::from(guild_id), id) {
+    Ok(true) => create_custom_event_deleted_embed(id),
+    Ok(false) => create_error_embed(
+      format!("No event `#{}` found.", id),
+      "Check the number with /event list.".to_string(),
+    ),
+    Err(e) => create_error_embed(
+      format!("Error while deleting the event: {}", e),
+      "Please try again later.".to_string(),
+    ),
+  };
+
+  ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+
+  Ok(())
+}
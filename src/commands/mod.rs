@@ -1,3 +1,8 @@
+pub mod anniversary;
+pub mod audit;
 pub mod birthday;
 pub mod color;
+pub mod color_palette;
+pub mod event;
 pub mod set_channel;
+pub mod settings;
\ No newline at end of file
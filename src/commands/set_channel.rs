@@ -1,14 +1,15 @@
 use crate::db::connection::establish_connection;
-use crate::db::queries::insert_guild_settings;
-use crate::utils::embed_utils::{create_error_embed, create_settings_embed};
+use crate::db::queries::{get_birthday_board, get_guild_settings, insert_guild_settings, list_birthdays, set_anniversary_channel, set_audit_channel, set_birthday_board, set_birthday_calendar};
+use crate::utils::birthday_utils::{create_birthday_board, refresh_birthday_calendar};
+use crate::utils::embed_utils::{create_anniversary_settings_embed, create_audit_settings_embed, create_board_settings_embed, create_calendar_settings_embed, create_error_embed, create_settings_embed};
 use crate::{Context, Error};
-use poise::serenity_prelude::ChannelId;
+use poise::serenity_prelude::{ChannelId, CreateMessage, MessageId};
 use poise::CreateReply;
 
 // Sets channels for specific purposes
 #[poise::command(
   slash_command,
-  subcommands("announce"),
+  subcommands("announce", "board", "calendar", "audit", "anniversaries"),
   required_permissions = "MANAGE_CHANNELS",
   subcommand_required
 )]
@@ -37,4 +38,148 @@ async fn announce(ctx: Context<'_>, channel: ChannelId) -> Result<(), Error> {
   }
 
   Ok(())
-}
\ No newline at end of file
+}
+
+/// Posts a public birthday board anyone can page through, replacing the previous one.
+#[poise::command(slash_command)]
+async fn board(ctx: Context<'_>, channel: ChannelId) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let birthdays = match list_birthdays(conn, i64::from(guild_id)) {
+    Ok(birthdays) => birthdays,
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while getting the birthdays: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+      return Ok(());
+    }
+  };
+
+  if let Ok(Some((old_channel_id, old_message_id))) = get_birthday_board(conn, i64::from(guild_id)) {
+    let _ = ChannelId::new(old_channel_id as u64)
+        .delete_message(ctx, MessageId::new(old_message_id as u64))
+        .await;
+  }
+
+  let (embed, components) = create_birthday_board(birthdays, 0);
+  let message = channel
+      .send_message(ctx, CreateMessage::default().embed(embed).components(components))
+      .await?;
+
+  match set_birthday_board(conn, i64::from(guild_id), Some((i64::from(channel), i64::from(message.id)))) {
+    Ok(_) => {
+      let embed = create_board_settings_embed(channel);
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while setting birthday board: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Keeps an auto-updating calendar of the next upcoming birthdays in the channel.
+#[poise::command(slash_command)]
+async fn calendar(
+  ctx: Context<'_>,
+  channel: ChannelId,
+  #[description = "How many upcoming birthdays to show (default 10)"]
+  #[min = 1]
+  #[max = 25]
+  size: Option<i32>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let size = size.unwrap_or(10);
+  let conn = &mut establish_connection();
+
+  // The old calendar would otherwise stay behind, frozen, in the previous channel.
+  if let Ok(Some(settings)) = get_guild_settings(conn, i64::from(guild_id))
+      && let (Some(old_channel_id), Some(old_message_id)) = (settings.calendar_channel_id, settings.calendar_message_id) {
+    let _ = ChannelId::new(old_channel_id as u64)
+        .delete_message(ctx, MessageId::new(old_message_id as u64))
+        .await;
+  }
+
+  if let Err(e) = set_birthday_calendar(conn, i64::from(guild_id), i64::from(channel), size) {
+    let embed = create_error_embed(
+      format!("Error while setting birthday calendar: {}", e),
+      "Please try again later.".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  match refresh_birthday_calendar(ctx.http(), conn, i64::from(guild_id)).await {
+    Ok(_) => {
+      let embed = create_calendar_settings_embed(channel, size);
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while posting birthday calendar: {}", e),
+        "Make sure I can send messages in that channel.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Posts every birthday and color change to the channel.
+#[poise::command(slash_command)]
+async fn audit(ctx: Context<'_>, channel: ChannelId) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_audit_channel(conn, i64::from(guild_id), Some(i64::from(channel))) {
+    Ok(_) => {
+      let embed = create_audit_settings_embed(channel);
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while setting audit log channel: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Announces members' server join anniversaries in the channel.
+#[poise::command(slash_command)]
+async fn anniversaries(ctx: Context<'_>, channel: ChannelId) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_anniversary_channel(conn, i64::from(guild_id), Some(i64::from(channel))) {
+    Ok(_) => {
+      let embed = create_anniversary_settings_embed(channel);
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while setting anniversary channel: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
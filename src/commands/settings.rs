@@ -0,0 +1,548 @@
+use crate::db::connection::establish_connection;
+use crate::db::queries::{get_guild_settings, set_anniversary_template, set_announcement_threads, set_birthday_approval, set_birthday_change_limits, set_birthday_info_extras, set_cake_days, set_calendar_style, set_color_contrast_policy, set_color_role_anchor, set_color_role_cleanup, set_palette_only, set_shared_color_roles, set_wish_delivery};
+use crate::utils::anniversary_utils::DEFAULT_ANNIVERSARY_TEMPLATE;
+use crate::utils::birthday_utils::ThreadArchive;
+use crate::utils::calendar_utils::{get_calendar_style, CalendarTheme};
+use crate::utils::color_role_utils::reorder_color_roles;
+use crate::utils::color_utils::{get_contrast_policy, ContrastPolicy, MAX_CONTRAST_THRESHOLD};
+use crate::utils::embed_utils::{create_error_embed, create_settings_updated_embed};
+use crate::utils::image_utils::FontFamily;
+use crate::{Context, Error};
+use poise::serenity_prelude::{ChannelId, Mentionable, ReactionType, Role};
+use poise::{ChoiceParameter, CreateReply};
+
+// Configures guild specific behaviour
+#[poise::command(
+  slash_command,
+  subcommands("calendar", "approval", "limits", "threads", "wishes", "anniversaries", "cakeday", "extras", "contrast", "palette", "colorroles", "colorcleanup", "colorposition"),
+  required_permissions = "MANAGE_GUILD",
+  subcommand_required
+)]
+pub async fn settings(_: Context<'_>) -> Result<(), Error> {
+  Ok(())
+}
+
+/// Sets the theme and font used for rendered birthday calendars.
+#[poise::command(slash_command)]
+async fn calendar(ctx: Context<'_>, theme: Option<CalendarTheme>, font: Option<FontFamily>) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let (current_theme, current_font) = get_calendar_style(settings.as_ref());
+  let theme = theme.unwrap_or(current_theme);
+  let font = font.unwrap_or(current_font);
+
+  match set_calendar_style(conn, i64::from(guild_id), theme.name(), font.name()) {
+    Ok(_) => {
+      let embed = create_settings_updated_embed(format!(
+        "Birthday calendars will use the **{}** theme with the **{}** font.",
+        theme.name(), font.name()
+      ));
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving calendar settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Requires moderator approval for birthdays members set for themselves.
+#[poise::command(slash_command)]
+async fn approval(
+  ctx: Context<'_>,
+  enabled: bool,
+  #[description = "Channel where approval requests are posted"] channel: Option<ChannelId>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let channel = channel.or_else(|| {
+    get_guild_settings(conn, i64::from(guild_id))
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.moderation_channel_id)
+        .map(|channel_id| ChannelId::new(channel_id as u64))
+  });
+
+  if enabled && channel.is_none() {
+    let embed = create_error_embed(
+      "A channel for approval requests is required.".to_string(),
+      "Run the command again with the channel option.".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  match set_birthday_approval(conn, i64::from(guild_id), enabled, channel.map(i64::from)) {
+    Ok(_) => {
+      let description = match channel {
+        Some(channel) if enabled => format!("Birthdays set by members now need approval in **{}**.", channel.mention()),
+        _ => "Birthdays set by members no longer need approval.".to_string(),
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving approval settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Limits how often members can change their own birthday (0 disables a limit).
+#[poise::command(slash_command)]
+async fn limits(
+  ctx: Context<'_>,
+  #[description = "Hours between changes"]
+  #[min = 0]
+  cooldown_hours: Option<i32>,
+  #[description = "Maximum changes per calendar year"]
+  #[min = 0]
+  changes_per_year: Option<i32>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let cooldown_hours = cooldown_hours
+      .or(settings.as_ref().map(|settings| settings.birthday_change_cooldown_hours))
+      .unwrap_or(0);
+  let changes_per_year = changes_per_year
+      .or(settings.as_ref().map(|settings| settings.birthday_changes_per_year))
+      .unwrap_or(0);
+
+  match set_birthday_change_limits(conn, i64::from(guild_id), cooldown_hours, changes_per_year) {
+    Ok(_) => {
+      let cooldown = if cooldown_hours > 0 { format!("**{}** hours", cooldown_hours) } else { "**no**".to_string() };
+      let cap = if changes_per_year > 0 { format!("at most **{}**", changes_per_year) } else { "**unlimited**".to_string() };
+      let embed = create_settings_updated_embed(format!(
+        "Members have {} cooldown and {} changes per year for their own birthday.",
+        cooldown, cap
+      ));
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving birthday limits: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Opens a discussion thread on birthday announcements and adds reactions to them.
+#[poise::command(slash_command)]
+async fn threads(
+  ctx: Context<'_>,
+  enabled: bool,
+  #[description = "When inactive threads get archived"] archive: Option<ThreadArchive>,
+  #[description = "Emojis to react with, separated by spaces (\"none\" to clear)"] reactions: Option<String>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let archive_minutes = archive
+      .map(|archive| archive.minutes())
+      .or(settings.as_ref().map(|settings| settings.thread_archive_minutes))
+      .unwrap_or(ThreadArchive::OneDay.minutes());
+  let reactions = match reactions {
+    Some(reactions) if reactions.trim().eq_ignore_ascii_case("none") => None,
+    Some(reactions) => Some(reactions),
+    None => settings.and_then(|settings| settings.announcement_reactions),
+  };
+
+  let guild_emojis = guild_id.emojis(ctx).await?;
+  let is_valid_reaction = |emoji: &str| match ReactionType::try_from(emoji) {
+    Ok(ReactionType::Custom { id, .. }) => guild_emojis.iter().any(|guild_emoji| guild_emoji.id == id),
+    Ok(ReactionType::Unicode(emoji)) => is_unicode_emoji(&emoji),
+    _ => false,
+  };
+
+  if let Some(invalid) = reactions
+      .as_deref()
+      .unwrap_or_default()
+      .split_whitespace()
+      .find(|emoji| !is_valid_reaction(emoji))
+  {
+    let embed = create_error_embed(
+      format!("**{}** is not a valid emoji.", invalid),
+      "Use standard emojis or this server's custom ones, separated by spaces, e.g. 🎉 🎂".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  match set_announcement_threads(conn, i64::from(guild_id), enabled, archive_minutes, reactions.as_deref()) {
+    Ok(_) => {
+      let threads = if enabled {
+        format!("Birthday announcements will get a thread archived after **{}** minutes of inactivity.", archive_minutes)
+      } else {
+        "Birthday announcements won't get a thread.".to_string()
+      };
+      let reactions = match reactions {
+        Some(reactions) => format!("The bot will react with {}.", reactions),
+        None => "The bot won't add reactions.".to_string(),
+      };
+
+      let embed = create_settings_updated_embed(format!("{}\n{}", threads, reactions));
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving thread settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Chooses whether birthday wishes are sent by DM or posted in the announcement channel.
+#[poise::command(slash_command)]
+async fn wishes(
+  ctx: Context<'_>,
+  #[description = "Deliver by DM (falls back to the announcement channel if DMs are closed)"] dm: bool,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_wish_delivery(conn, i64::from(guild_id), dm) {
+    Ok(_) => {
+      let description = if dm {
+        "Birthday wishes will be delivered by DM.".to_string()
+      } else {
+        "Birthday wishes will be posted in the announcement channel.".to_string()
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving wish settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Whether the text is a single standard emoji, including skin tones, flags, keycaps and ZWJ sequences.
+fn is_unicode_emoji(text: &str) -> bool {
+  let is_pictographic = |c: char| matches!(c as u32,
+    0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2300..=0x23FF | 0x2B00..=0x2BFF | 0x2190..=0x21FF | 0x25A0..=0x25FF
+    | 0x2934 | 0x2935 | 0x3030 | 0x303D | 0x3297 | 0x3299 | 0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139 | 0x24C2);
+  let is_modifier = |c: char| matches!(c as u32, 0x200D | 0xFE0F | 0x20E3 | 0xE0020..=0xE007F);
+  let is_keycap = text.ends_with('\u{20E3}');
+
+  let is_allowed = |c: char| is_pictographic(c) || is_modifier(c) || (is_keycap && matches!(c, '0'..='9' | '#' | '*'));
+
+  (text.chars().any(is_pictographic) || is_keycap) && text.chars().all(is_allowed)
+}
+
+/// Sets the message used for server join anniversaries. Leave empty to restore the default.
+#[poise::command(slash_command)]
+async fn anniversaries(
+  ctx: Context<'_>,
+  #[description = "Use {user} for the member and {years} for the number of years"]
+  #[max_length = 200]
+  template: Option<String>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let template = template.filter(|template| !template.trim().is_empty());
+
+  match set_anniversary_template(conn, i64::from(guild_id), template.as_deref()) {
+    Ok(_) => {
+      let embed = create_settings_updated_embed(format!(
+        "Anniversaries will be announced as:\n> {}",
+        template.as_deref().unwrap_or(DEFAULT_ANNIVERSARY_TEMPLATE)
+      ));
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving anniversary settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Chooses which cake days (the server's creation and the day I was added) get announced.
+#[poise::command(slash_command)]
+async fn cakeday(
+  ctx: Context<'_>,
+  #[description = "Announce the day this server was created"] server: Option<bool>,
+  #[description = "Announce the day I was added to this server"] bot: Option<bool>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let server = server.unwrap_or(settings.as_ref().is_none_or(|settings| settings.guild_cake_day));
+  let bot = bot.unwrap_or(settings.as_ref().is_none_or(|settings| settings.bot_cake_day));
+
+  match set_cake_days(conn, i64::from(guild_id), server, bot) {
+    Ok(_) => {
+      let description = match (server, bot) {
+        (true, true) => "The server's and my cake day will both be announced.",
+        (true, false) => "Only the server's cake day will be announced.",
+        (false, true) => "Only my cake day will be announced.",
+        (false, false) => "Cake days won't be announced.",
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description.to_string())).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving cake day settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Shows or hides the zodiac, birthstone and weekday in /birthday info.
+#[poise::command(slash_command)]
+async fn extras(ctx: Context<'_>, enabled: bool) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_birthday_info_extras(conn, i64::from(guild_id), enabled) {
+    Ok(_) => {
+      let description = if enabled {
+        "Birthday info will include the zodiac signs, birthstone and weekday.".to_string()
+      } else {
+        "Birthday info will only show the date and the next celebration.".to_string()
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving birthday info settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Chooses what /color set does with colors that are hard to read on Discord's dark or light theme.
+#[poise::command(slash_command)]
+async fn contrast(
+  ctx: Context<'_>,
+  policy: ContrastPolicy,
+  #[description = "Minimum contrast ratio on both themes (default 2.0)"]
+  #[min = 1.0]
+  #[max = 3.5]
+  threshold: Option<f64>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  let settings = get_guild_settings(conn, i64::from(guild_id)).ok().flatten();
+  let (_, current_threshold) = get_contrast_policy(settings.as_ref());
+  let threshold = threshold.unwrap_or(current_threshold).clamp(1.0, MAX_CONTRAST_THRESHOLD);
+
+  match set_color_contrast_policy(conn, i64::from(guild_id), policy.name(), threshold) {
+    Ok(_) => {
+      let description = match policy {
+        ContrastPolicy::Off => "Colors won't be checked for readability.".to_string(),
+        ContrastPolicy::Warn => format!("Colors below **{:.1}:1** contrast will be set with a warning.", threshold),
+        ContrastPolicy::Adjust => format!("Colors below **{:.1}:1** contrast will be adjusted until they're readable.", threshold),
+        ContrastPolicy::Reject => format!("Colors below **{:.1}:1** contrast will be rejected.", threshold),
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving contrast settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Restricts /color set to the colors in the server palette.
+#[poise::command(slash_command)]
+async fn palette(ctx: Context<'_>, #[description = "Only allow palette colors"] enabled: bool) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_palette_only(conn, i64::from(guild_id), enabled) {
+    Ok(_) => {
+      let description = if enabled {
+        "Members can only pick colors from the palette (/colorpalette).".to_string()
+      } else {
+        "Members can pick any color; palette colors are offered as suggestions.".to_string()
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving palette settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Shares one role per color between everyone who picks it, instead of one role per member.
+#[poise::command(slash_command)]
+async fn colorroles(ctx: Context<'_>, #[description = "Share a role between members with the same color"] shared: bool) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_shared_color_roles(conn, i64::from(guild_id), shared) {
+    Ok(_) => {
+      let description = if shared {
+        "Members with the same color will share a role. Existing roles switch over the next time members use /color set.".to_string()
+      } else {
+        "Every member will get their own color role.".to_string()
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving color role settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Deletes orphaned per-user color roles automatically every night.
+#[poise::command(slash_command)]
+async fn colorcleanup(ctx: Context<'_>, enabled: bool) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  match set_color_role_cleanup(conn, i64::from(guild_id), enabled) {
+    Ok(_) => {
+      let description = if enabled {
+        "Color roles of members who left, or that nobody has, will be deleted every night.".to_string()
+      } else {
+        "Orphaned color roles will only be deleted with /color cleanup.".to_string()
+      };
+
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Error while saving cleanup settings: {}", e),
+        "Please try again later.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Places color roles right below a role, so they don't override moderator colors.
+#[poise::command(slash_command)]
+async fn colorposition(
+  ctx: Context<'_>,
+  #[description = "Color roles go right below this role; leave empty to put them at the top"] anchor: Option<Role>,
+) -> Result<(), Error> {
+  let guild_id = ctx.guild_id().unwrap();
+  let conn = &mut establish_connection();
+
+  if let Err(e) = set_color_role_anchor(conn, i64::from(guild_id), anchor.as_ref().map(|role| i64::from(role.id))) {
+    let embed = create_error_embed(
+      format!("Error while saving color role position: {}", e),
+      "Please try again later.".to_string());
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    return Ok(());
+  }
+
+  let Some(anchor) = anchor else {
+    let description = "New color roles will be placed above every other role.".to_string();
+    ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    return Ok(());
+  };
+
+  ctx.defer_ephemeral().await?;
+
+  match reorder_color_roles(ctx.http(), conn, guild_id, anchor.id).await {
+    Ok(_) => {
+      let description = format!("Color roles are now placed right below {}.", anchor.mention());
+      ctx.send(CreateReply::default().embed(create_settings_updated_embed(description)).ephemeral(true)).await?;
+    }
+    Err(e) => {
+      let embed = create_error_embed(
+        format!("Saved, but moving the existing color roles failed: {}", e),
+        "Make sure my role is above the anchor role.".to_string());
+
+      ctx.send(CreateReply::default().embed(embed).ephemeral(true)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::is_unicode_emoji;
+
+  #[test]
+  fn accepts_standard_emojis() {
+    for emoji in ["🎉", "🎂", "❤️", "👍🏽", "🇱🇹", "👨‍👩‍👧", "1️⃣", "#️⃣", "©️"] {
+      assert!(is_unicode_emoji(emoji), "{} should be accepted", emoji);
+    }
+  }
+
+  #[test]
+  fn rejects_text() {
+    for text in ["a", "abc", "1", "#", ":tada:", "🎉abc", "️"] {
+      assert!(!is_unicode_emoji(text), "{} should be rejected", text);
+    }
+  }
+}
@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use diesel::sql_types::{BigInt, Double, Integer, Nullable};
 use diesel::{Insertable, Queryable, QueryableByName, Selectable};
 
 #[derive(Queryable, QueryableByName, Selectable)]
@@ -9,6 +10,9 @@ pub struct Birthday {
   pub guild_id: i64,
   pub date: chrono::NaiveDate,
   pub announced_this_year: bool,
+  pub approved: bool,
+  /// A submitted date waiting for a moderator. `date` stays the approved one until then.
+  pub pending_date: Option<chrono::NaiveDate>,
 }
 
 #[derive(Insertable)]
@@ -18,6 +22,8 @@ pub struct NewBirthday<'a> {
   pub guild_id: &'a i64,
   pub date: &'a chrono::NaiveDate,
   pub announced_this_year: &'a bool,
+  pub approved: &'a bool,
+  pub pending_date: Option<&'a chrono::NaiveDate>,
 }
 
 #[derive(Queryable, QueryableByName, Selectable)]
@@ -26,6 +32,35 @@ pub struct GuildSettings {
   pub id: i32,
   pub guild_id: i64,
   pub announcements_channel_id: Option<i64>,
+  pub board_channel_id: Option<i64>,
+  pub board_message_id: Option<i64>,
+  pub calendar_channel_id: Option<i64>,
+  pub calendar_message_id: Option<i64>,
+  pub calendar_size: i32,
+  pub calendar_theme: String,
+  pub calendar_font: String,
+  pub require_birthday_approval: bool,
+  pub moderation_channel_id: Option<i64>,
+  pub birthday_change_cooldown_hours: i32,
+  pub birthday_changes_per_year: i32,
+  pub audit_channel_id: Option<i64>,
+  pub announcement_threads: bool,
+  pub thread_archive_minutes: i32,
+  pub announcement_reactions: Option<String>,
+  pub deliver_wishes_by_dm: bool,
+  pub anniversary_channel_id: Option<i64>,
+  pub anniversary_template: Option<String>,
+  pub anniversaries_announced_on: Option<chrono::NaiveDate>,
+  pub guild_cake_day: bool,
+  pub bot_cake_day: bool,
+  pub cake_days_announced_on: Option<chrono::NaiveDate>,
+  pub birthday_info_extras: bool,
+  pub color_contrast_policy: String,
+  pub color_contrast_threshold: f64,
+  pub palette_only: bool,
+  pub shared_color_roles: bool,
+  pub color_role_cleanup: bool,
+  pub color_role_anchor_id: Option<i64>,
 }
 
 #[derive(Insertable)]
@@ -34,3 +69,135 @@ pub struct NewGuildSettings {
   pub guild_id: i64,
   pub announcements_channel_id: Option<i64>,
 }
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::birthday_changes)]
+pub struct NewBirthdayChange {
+  pub user_id: i64,
+  pub guild_id: i64,
+  pub changed_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::birthday_wishes)]
+pub struct BirthdayWish {
+  pub id: i32,
+  pub guild_id: i64,
+  pub author_id: i64,
+  pub recipient_id: i64,
+  pub message: String,
+  pub created_at: chrono::NaiveDateTime,
+  pub delivered: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::birthday_wishes)]
+pub struct NewBirthdayWish<'a> {
+  pub guild_id: i64,
+  pub author_id: i64,
+  pub recipient_id: i64,
+  pub message: &'a str,
+  pub created_at: chrono::NaiveDateTime,
+  pub delivered: bool,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::color_palette)]
+pub struct PaletteColor {
+  pub id: i32,
+  pub guild_id: i64,
+  pub name: String,
+  pub color: String,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::color_history)]
+pub struct ColorHistoryEntry {
+  pub id: i32,
+  pub guild_id: i64,
+  pub user_id: i64,
+  pub color: String,
+  pub changed_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::color_roles)]
+pub struct ColorRole {
+  pub id: i32,
+  pub guild_id: i64,
+  pub role_id: i64,
+  pub color: String,
+}
+
+/// A per-user color role the bot created, named after its member.
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::personal_color_roles)]
+pub struct PersonalColorRole {
+  pub id: i32,
+  pub guild_id: i64,
+  pub user_id: i64,
+  pub role_id: i64,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::custom_events)]
+pub struct CustomEvent {
+  pub id: i32,
+  pub guild_id: i64,
+  pub name: String,
+  pub date: chrono::NaiveDate,
+  pub recurrence: String,
+  pub role_id: Option<i64>,
+  pub created_by: i64,
+  pub last_announced_on: Option<chrono::NaiveDate>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::custom_events)]
+pub struct NewCustomEvent<'a> {
+  pub guild_id: i64,
+  pub name: &'a str,
+  pub date: chrono::NaiveDate,
+  pub recurrence: &'a str,
+  pub role_id: Option<i64>,
+  pub created_by: i64,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::db::schema::audit_log)]
+pub struct AuditEntry {
+  pub id: i32,
+  pub guild_id: i64,
+  pub actor_id: i64,
+  pub target_id: i64,
+  pub action: String,
+  pub old_value: Option<String>,
+  pub new_value: Option<String>,
+  pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::audit_log)]
+pub struct NewAuditEntry<'a> {
+  pub guild_id: i64,
+  pub actor_id: i64,
+  pub target_id: i64,
+  pub action: &'a str,
+  pub old_value: Option<&'a str>,
+  pub new_value: Option<&'a str>,
+  pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(QueryableByName)]
+pub struct GroupedCount {
+  #[diesel(sql_type = Integer)]
+  pub key: i32,
+  #[diesel(sql_type = BigInt)]
+  pub count: i64,
+}
+
+#[derive(QueryableByName)]
+pub struct AverageAge {
+  #[diesel(sql_type = Nullable<Double>)]
+  pub average: Option<f64>,
+}
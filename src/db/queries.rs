@@ -1,28 +1,79 @@
-use crate::db::models::{Birthday, NewBirthday, NewGuildSettings};
+use crate::db::models::{AuditEntry, AverageAge, Birthday, BirthdayWish, ColorHistoryEntry, ColorRole, CustomEvent, GroupedCount, GuildSettings, NewBirthday, NewAuditEntry, NewBirthdayChange, NewBirthdayWish, NewCustomEvent, NewGuildSettings, PaletteColor, PersonalColorRole};
+use crate::db::schema::anniversary_opt_outs;
+use crate::db::schema::audit_log;
+use crate::db::schema::birthday_announcements;
+use crate::db::schema::birthday_changes;
+use crate::db::schema::birthday_wishes;
 use crate::db::schema::birthdays;
+use crate::db::schema::color_history;
+use crate::db::schema::color_palette;
+use crate::db::schema::color_role_holders;
+use crate::db::schema::color_roles;
+use crate::db::schema::custom_events;
 use crate::db::schema::guild_settings;
-use chrono::{Datelike, Local, NaiveDate};
+use crate::db::schema::personal_color_roles;
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Utc};
 use diesel::result::Error;
-use diesel::{sql_query, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper, SqliteConnection};
+use diesel::sql_types::BigInt;
+use diesel::{sql_query, BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper, SqliteConnection};
 
-pub fn insert_birthday(conn: &mut SqliteConnection, user: i64, guild_id: i64, date: NaiveDate) -> Result<(), Error> {
+pub fn insert_birthday(conn: &mut SqliteConnection, user: i64, guild_id: i64, date: NaiveDate, announced: bool) -> Result<(), Error> {
   let new_birthday = NewBirthday {
     user_id: &user,
     guild_id: &guild_id,
     date: &date,
-    announced_this_year: &false,
+    announced_this_year: &announced,
+    approved: &true,
+    pending_date: None,
   };
 
   diesel::insert_into(birthdays::table)
       .values(&new_birthday)
       .on_conflict((birthdays::user_id, birthdays::guild_id))
       .do_update()
-      .set((birthdays::date.eq(date), birthdays::announced_this_year.eq(false)))
+      .set((
+        birthdays::date.eq(date),
+        birthdays::announced_this_year.eq(announced),
+        birthdays::approved.eq(true),
+        birthdays::pending_date.eq(None::<NaiveDate>),
+      ))
       .execute(conn)?;
 
   Ok(())
 }
 
+/// Submits a birthday for approval. An already approved birthday stays in place until the new date is approved.
+pub fn submit_pending_birthday(conn: &mut SqliteConnection, user: i64, guild_id: i64, date: NaiveDate) -> Result<(), Error> {
+  let new_birthday = NewBirthday {
+    user_id: &user,
+    guild_id: &guild_id,
+    date: &date,
+    announced_this_year: &false,
+    approved: &false,
+    pending_date: Some(&date),
+  };
+
+  conn.transaction(|conn| {
+    let updated = diesel::update(birthdays::table
+        .filter(birthdays::user_id.eq(user))
+        .filter(birthdays::guild_id.eq(guild_id))
+        .filter(birthdays::approved.eq(true)))
+        .set(birthdays::pending_date.eq(date))
+        .execute(conn)?;
+
+    if updated == 0 {
+      diesel::insert_into(birthdays::table)
+          .values(&new_birthday)
+          .on_conflict((birthdays::user_id, birthdays::guild_id))
+          .do_update()
+          .set((birthdays::date.eq(date), birthdays::pending_date.eq(date)))
+          .execute(conn)?;
+    }
+
+    Ok(())
+  })
+}
+
 pub fn get_birthday(conn: &mut SqliteConnection, user: i64, guild_id: i64) -> Result<Option<Birthday>, Error> {
   let birthday = birthdays::table
       .filter(birthdays::user_id.eq(user))
@@ -38,7 +89,7 @@ pub fn get_birthdays_today(conn: &mut SqliteConnection) -> Result<Vec<Birthday>,
   let today_month_day = (today.month(), today.day());
 
   let query = format!(
-    "SELECT * FROM birthdays WHERE strftime('%m', date) = '{:02}' AND strftime('%d', date) = '{:02}' AND announced_this_year = 0",
+    "SELECT * FROM birthdays WHERE strftime('%m', date) = '{:02}' AND strftime('%d', date) = '{:02}' AND announced_this_year = 0 AND approved = 1",
     today_month_day.0,
     today_month_day.1
   );
@@ -59,9 +110,98 @@ pub fn delete_birthday(conn: &mut SqliteConnection, birthday: &Birthday) -> Resu
   Ok(())
 }
 
+/// Re-inserts a deleted birthday as it was. Returns `false` if a birthday was set again in the meantime.
+pub fn restore_birthday(conn: &mut SqliteConnection, birthday: &Birthday) -> Result<bool, Error> {
+  let restored_birthday = NewBirthday {
+    user_id: &birthday.user_id,
+    guild_id: &birthday.guild_id,
+    date: &birthday.date,
+    announced_this_year: &birthday.announced_this_year,
+    approved: &birthday.approved,
+    pending_date: birthday.pending_date.as_ref(),
+  };
+
+  let inserted = diesel::insert_into(birthdays::table)
+      .values(&restored_birthday)
+      .on_conflict_do_nothing()
+      .execute(conn)?;
+
+  Ok(inserted > 0)
+}
+
+pub fn record_birthday_change(conn: &mut SqliteConnection, user: i64, guild_id: i64, changed_at: NaiveDateTime) -> Result<(), Error> {
+  diesel::insert_into(birthday_changes::table)
+      .values(&NewBirthdayChange { user_id: user, guild_id, changed_at })
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn get_last_birthday_change(conn: &mut SqliteConnection, user: i64, guild_id: i64) -> Result<Option<NaiveDateTime>, Error> {
+  birthday_changes::table
+      .filter(birthday_changes::user_id.eq(user))
+      .filter(birthday_changes::guild_id.eq(guild_id))
+      .select(diesel::dsl::max(birthday_changes::changed_at))
+      .first(conn)
+}
+
+pub fn count_birthday_changes_since(conn: &mut SqliteConnection, user: i64, guild_id: i64, since: NaiveDateTime) -> Result<i64, Error> {
+  birthday_changes::table
+      .filter(birthday_changes::user_id.eq(user))
+      .filter(birthday_changes::guild_id.eq(guild_id))
+      .filter(birthday_changes::changed_at.ge(since))
+      .count()
+      .get_result(conn)
+}
+
+pub fn get_birthday_by_id(conn: &mut SqliteConnection, birthday_id: i32) -> Result<Option<Birthday>, Error> {
+  birthdays::table
+      .find(birthday_id)
+      .select(Birthday::as_select())
+      .first(conn)
+      .optional()
+}
+
+/// Approves a pending birthday. Returns `false` if it is no longer pending with that date.
+pub fn approve_birthday(conn: &mut SqliteConnection, birthday_id: i32, date: NaiveDate, announced: bool) -> Result<bool, Error> {
+  let updated = diesel::update(birthdays::table
+      .filter(birthdays::id.eq(birthday_id))
+      .filter(birthdays::pending_date.eq(date)))
+      .set((
+        birthdays::date.eq(date),
+        birthdays::announced_this_year.eq(announced),
+        birthdays::approved.eq(true),
+        birthdays::pending_date.eq(None::<NaiveDate>),
+      ))
+      .execute(conn)?;
+
+  Ok(updated > 0)
+}
+
+/// Drops a pending date, keeping a previously approved birthday. Returns `false` if it is no longer pending with that date.
+pub fn reject_birthday(conn: &mut SqliteConnection, birthday_id: i32, date: NaiveDate) -> Result<bool, Error> {
+  conn.transaction(|conn| {
+    let cleared = diesel::update(birthdays::table
+        .filter(birthdays::id.eq(birthday_id))
+        .filter(birthdays::pending_date.eq(date))
+        .filter(birthdays::approved.eq(true)))
+        .set(birthdays::pending_date.eq(None::<NaiveDate>))
+        .execute(conn)?;
+
+    let deleted = diesel::delete(birthdays::table
+        .filter(birthdays::id.eq(birthday_id))
+        .filter(birthdays::pending_date.eq(date))
+        .filter(birthdays::approved.eq(false)))
+        .execute(conn)?;
+
+    Ok(cleared + deleted > 0)
+  })
+}
+
 pub fn list_birthdays(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<Birthday>, Error> {
   let results = birthdays::table
       .filter(birthdays::guild_id.eq(guild_id))
+      .filter(birthdays::approved.eq(true))
       .select(Birthday::as_select())
       .load(conn)
       .expect("Error loading birthdays");
@@ -69,6 +209,79 @@ pub fn list_birthdays(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<
   Ok(results)
 }
 
+pub fn record_birthday_announcements(conn: &mut SqliteConnection, guild_id: i64, user_ids: &[i64], announced_at: NaiveDateTime) -> Result<(), Error> {
+  let rows = user_ids
+      .iter()
+      .map(|user_id| (
+        birthday_announcements::guild_id.eq(guild_id),
+        birthday_announcements::user_id.eq(*user_id),
+        birthday_announcements::announced_at.eq(announced_at),
+      ))
+      .collect::<Vec<_>>();
+
+  diesel::insert_into(birthday_announcements::table)
+      .values(&rows)
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn count_user_birthday_announcements_since(conn: &mut SqliteConnection, guild_id: i64, user_id: i64, since: NaiveDateTime) -> Result<i64, Error> {
+  birthday_announcements::table
+      .filter(birthday_announcements::guild_id.eq(guild_id))
+      .filter(birthday_announcements::user_id.eq(user_id))
+      .filter(birthday_announcements::announced_at.ge(since))
+      .count()
+      .get_result(conn)
+}
+
+pub fn count_birthday_announcements_since(conn: &mut SqliteConnection, guild_id: i64, since: NaiveDateTime) -> Result<i64, Error> {
+  birthday_announcements::table
+      .filter(birthday_announcements::guild_id.eq(guild_id))
+      .filter(birthday_announcements::announced_at.ge(since))
+      .count()
+      .get_result(conn)
+}
+
+pub fn count_birthdays(conn: &mut SqliteConnection, guild_id: i64) -> Result<i64, Error> {
+  birthdays::table
+      .filter(birthdays::guild_id.eq(guild_id))
+      .filter(birthdays::approved.eq(true))
+      .count()
+      .get_result(conn)
+}
+
+/// Number of birthdays per month (1-12), busiest month first.
+pub fn count_birthdays_by_month(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<GroupedCount>, Error> {
+  sql_query(
+    "SELECT CAST(strftime('%m', date) AS INTEGER) AS key, COUNT(*) AS count FROM birthdays \
+     WHERE guild_id = ? AND approved = 1 GROUP BY key ORDER BY count DESC, key ASC",
+  )
+      .bind::<BigInt, _>(guild_id)
+      .load(conn)
+}
+
+/// Number of birthdays falling on each weekday (0 = Sunday) this year.
+pub fn count_birthdays_by_weekday(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<GroupedCount>, Error> {
+  sql_query(
+    "SELECT CAST(strftime('%w', strftime('%Y', 'now') || substr(date, 5)) AS INTEGER) AS key, COUNT(*) AS count \
+     FROM birthdays WHERE guild_id = ? AND approved = 1 GROUP BY key ORDER BY key ASC",
+  )
+      .bind::<BigInt, _>(guild_id)
+      .load(conn)
+}
+
+pub fn get_average_age(conn: &mut SqliteConnection, guild_id: i64) -> Result<Option<f64>, Error> {
+  let result = sql_query(
+    "SELECT AVG((strftime('%Y', 'now') - strftime('%Y', date)) - (strftime('%m-%d', 'now') < strftime('%m-%d', date))) \
+     AS average FROM birthdays WHERE guild_id = ? AND approved = 1",
+  )
+      .bind::<BigInt, _>(guild_id)
+      .get_result::<AverageAge>(conn)?;
+
+  Ok(result.average)
+}
+
 pub fn update_announced_value(conn: &mut SqliteConnection, birthday_ids: Vec<i32>) -> Result<(), Error> {
   diesel::update(birthdays::table.filter(birthdays::id.eq_any(birthday_ids)))
       .set(birthdays::announced_this_year.eq(true))
@@ -101,6 +314,354 @@ pub fn reset_announced_flags(conn: &mut SqliteConnection) -> Result<(), Error> {
   Ok(())
 }
 
+// BIRTHDAY WISHES
+/// Stores the wish, replacing the author's previous wish for the same member.
+pub fn upsert_birthday_wish(conn: &mut SqliteConnection, wish: &NewBirthdayWish) -> Result<(), Error> {
+  diesel::insert_into(birthday_wishes::table)
+      .values(wish)
+      .on_conflict((birthday_wishes::guild_id, birthday_wishes::author_id, birthday_wishes::recipient_id))
+      .do_update()
+      .set((
+        birthday_wishes::message.eq(wish.message),
+        birthday_wishes::created_at.eq(wish.created_at),
+        birthday_wishes::delivered.eq(false),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn get_pending_birthday_wish(conn: &mut SqliteConnection, guild_id: i64, author_id: i64, recipient_id: i64) -> Result<Option<BirthdayWish>, Error> {
+  birthday_wishes::table
+      .filter(birthday_wishes::guild_id.eq(guild_id))
+      .filter(birthday_wishes::author_id.eq(author_id))
+      .filter(birthday_wishes::recipient_id.eq(recipient_id))
+      .filter(birthday_wishes::delivered.eq(false))
+      .select(BirthdayWish::as_select())
+      .first(conn)
+      .optional()
+}
+
+/// Deletes an undelivered wish. Returns `false` if there was nothing to delete.
+pub fn delete_birthday_wish(conn: &mut SqliteConnection, guild_id: i64, author_id: i64, recipient_id: i64) -> Result<bool, Error> {
+  let deleted = diesel::delete(birthday_wishes::table
+      .filter(birthday_wishes::guild_id.eq(guild_id))
+      .filter(birthday_wishes::author_id.eq(author_id))
+      .filter(birthday_wishes::recipient_id.eq(recipient_id))
+      .filter(birthday_wishes::delivered.eq(false)))
+      .execute(conn)?;
+
+  Ok(deleted > 0)
+}
+
+pub fn list_pending_birthday_wishes(conn: &mut SqliteConnection, guild_id: i64, recipient_id: i64) -> Result<Vec<BirthdayWish>, Error> {
+  birthday_wishes::table
+      .filter(birthday_wishes::guild_id.eq(guild_id))
+      .filter(birthday_wishes::recipient_id.eq(recipient_id))
+      .filter(birthday_wishes::delivered.eq(false))
+      .order(birthday_wishes::created_at.asc())
+      .select(BirthdayWish::as_select())
+      .load(conn)
+}
+
+pub fn mark_birthday_wishes_delivered(conn: &mut SqliteConnection, wish_ids: Vec<i32>) -> Result<(), Error> {
+  diesel::update(birthday_wishes::table.filter(birthday_wishes::id.eq_any(wish_ids)))
+      .set(birthday_wishes::delivered.eq(true))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+// COLOR PALETTE
+/// Adds the color to the palette, replacing the color of an entry with the same name.
+/// Names are unique regardless of case; re-adding a name takes over its new spelling.
+pub fn upsert_palette_color(conn: &mut SqliteConnection, guild_id: i64, name: &str, color: &str) -> Result<(), Error> {
+  diesel::insert_into(color_palette::table)
+      .values((
+        color_palette::guild_id.eq(guild_id),
+        color_palette::name.eq(name),
+        color_palette::color.eq(color),
+      ))
+      .on_conflict((color_palette::guild_id, color_palette::name))
+      .do_update()
+      .set((color_palette::name.eq(name), color_palette::color.eq(color)))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn delete_palette_color(conn: &mut SqliteConnection, guild_id: i64, name: &str) -> Result<bool, Error> {
+  let deleted = diesel::delete(color_palette::table
+      .filter(color_palette::guild_id.eq(guild_id))
+      .filter(color_palette::name.eq(name)))
+      .execute(conn)?;
+
+  Ok(deleted > 0)
+}
+
+pub fn list_palette_colors(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<PaletteColor>, Error> {
+  color_palette::table
+      .filter(color_palette::guild_id.eq(guild_id))
+      .order(color_palette::name.asc())
+      .select(PaletteColor::as_select())
+      .load(conn)
+}
+
+// COLOR HISTORY
+pub fn insert_color_history(conn: &mut SqliteConnection, guild_id: i64, user_id: i64, color: &str) -> Result<(), Error> {
+  diesel::insert_into(color_history::table)
+      .values((
+        color_history::guild_id.eq(guild_id),
+        color_history::user_id.eq(user_id),
+        color_history::color.eq(color),
+        color_history::changed_at.eq(Utc::now().naive_utc()),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+/// Newest first.
+pub fn list_color_history(conn: &mut SqliteConnection, guild_id: i64, user_id: i64, limit: i64) -> Result<Vec<ColorHistoryEntry>, Error> {
+  color_history::table
+      .filter(color_history::guild_id.eq(guild_id))
+      .filter(color_history::user_id.eq(user_id))
+      .order((color_history::changed_at.desc(), color_history::id.desc()))
+      .limit(limit)
+      .select(ColorHistoryEntry::as_select())
+      .load(conn)
+}
+
+pub fn delete_color_history_entries(conn: &mut SqliteConnection, ids: &[i32]) -> Result<(), Error> {
+  diesel::delete(color_history::table.filter(color_history::id.eq_any(ids))).execute(conn)?;
+
+  Ok(())
+}
+
+// SHARED COLOR ROLES
+pub fn get_color_role_by_color(conn: &mut SqliteConnection, guild_id: i64, color: &str) -> Result<Option<ColorRole>, Error> {
+  color_roles::table
+      .filter(color_roles::guild_id.eq(guild_id))
+      .filter(color_roles::color.eq(color))
+      .select(ColorRole::as_select())
+      .first(conn)
+      .optional()
+}
+
+pub fn get_color_role(conn: &mut SqliteConnection, role_id: i64) -> Result<Option<ColorRole>, Error> {
+  color_roles::table
+      .filter(color_roles::role_id.eq(role_id))
+      .select(ColorRole::as_select())
+      .first(conn)
+      .optional()
+}
+
+pub fn insert_color_role(conn: &mut SqliteConnection, guild_id: i64, role_id: i64, color: &str) -> Result<(), Error> {
+  diesel::insert_into(color_roles::table)
+      .values((
+        color_roles::guild_id.eq(guild_id),
+        color_roles::role_id.eq(role_id),
+        color_roles::color.eq(color),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn list_color_roles(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<ColorRole>, Error> {
+  color_roles::table
+      .filter(color_roles::guild_id.eq(guild_id))
+      .select(ColorRole::as_select())
+      .load(conn)
+}
+
+/// Forgets the shared role together with everyone recorded as holding it.
+pub fn delete_color_role(conn: &mut SqliteConnection, role_id: i64) -> Result<(), Error> {
+  conn.transaction(|conn| {
+    diesel::delete(color_role_holders::table.filter(color_role_holders::role_id.eq(role_id))).execute(conn)?;
+    diesel::delete(color_roles::table.filter(color_roles::role_id.eq(role_id))).execute(conn)?;
+
+    Ok(())
+  })
+}
+
+pub fn get_color_role_holder(conn: &mut SqliteConnection, guild_id: i64, user_id: i64) -> Result<Option<i64>, Error> {
+  color_role_holders::table
+      .filter(color_role_holders::guild_id.eq(guild_id))
+      .filter(color_role_holders::user_id.eq(user_id))
+      .select(color_role_holders::role_id)
+      .first(conn)
+      .optional()
+}
+
+pub fn set_color_role_holder(conn: &mut SqliteConnection, guild_id: i64, user_id: i64, role_id: i64) -> Result<(), Error> {
+  diesel::insert_into(color_role_holders::table)
+      .values((
+        color_role_holders::guild_id.eq(guild_id),
+        color_role_holders::user_id.eq(user_id),
+        color_role_holders::role_id.eq(role_id),
+      ))
+      .on_conflict((color_role_holders::guild_id, color_role_holders::user_id))
+      .do_update()
+      .set(color_role_holders::role_id.eq(role_id))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn delete_color_role_holder(conn: &mut SqliteConnection, guild_id: i64, user_id: i64) -> Result<(), Error> {
+  diesel::delete(color_role_holders::table
+      .filter(color_role_holders::guild_id.eq(guild_id))
+      .filter(color_role_holders::user_id.eq(user_id)))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn count_color_role_holders(conn: &mut SqliteConnection, role_id: i64) -> Result<i64, Error> {
+  color_role_holders::table
+      .filter(color_role_holders::role_id.eq(role_id))
+      .count()
+      .get_result(conn)
+}
+
+// PERSONAL COLOR ROLES
+pub fn track_personal_color_role(conn: &mut SqliteConnection, guild_id: i64, user_id: i64, role_id: i64) -> Result<(), Error> {
+  diesel::insert_into(personal_color_roles::table)
+      .values((
+        personal_color_roles::guild_id.eq(guild_id),
+        personal_color_roles::user_id.eq(user_id),
+        personal_color_roles::role_id.eq(role_id),
+      ))
+      .on_conflict((personal_color_roles::guild_id, personal_color_roles::user_id))
+      .do_update()
+      .set(personal_color_roles::role_id.eq(role_id))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn list_personal_color_roles(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<PersonalColorRole>, Error> {
+  personal_color_roles::table
+      .filter(personal_color_roles::guild_id.eq(guild_id))
+      .select(PersonalColorRole::as_select())
+      .load(conn)
+}
+
+pub fn forget_personal_color_role(conn: &mut SqliteConnection, role_id: i64) -> Result<(), Error> {
+  diesel::delete(personal_color_roles::table.filter(personal_color_roles::role_id.eq(role_id))).execute(conn)?;
+
+  Ok(())
+}
+
+// CUSTOM EVENTS
+pub fn insert_custom_event(conn: &mut SqliteConnection, event: &NewCustomEvent) -> Result<(), Error> {
+  diesel::insert_into(custom_events::table)
+      .values(event)
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn list_custom_events(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<CustomEvent>, Error> {
+  custom_events::table
+      .filter(custom_events::guild_id.eq(guild_id))
+      .order(custom_events::id.asc())
+      .select(CustomEvent::as_select())
+      .load(conn)
+}
+
+pub fn delete_custom_event(conn: &mut SqliteConnection, guild_id: i64, event_id: i32) -> Result<bool, Error> {
+  let deleted = diesel::delete(custom_events::table
+      .filter(custom_events::guild_id.eq(guild_id))
+      .filter(custom_events::id.eq(event_id)))
+      .execute(conn)?;
+
+  Ok(deleted > 0)
+}
+
+/// Events that started on or before today and haven't been announced today yet.
+pub fn list_unannounced_custom_events(conn: &mut SqliteConnection, today: NaiveDate) -> Result<Vec<CustomEvent>, Error> {
+  custom_events::table
+      .filter(custom_events::date.le(today))
+      .filter(custom_events::last_announced_on.is_null()
+          .or(custom_events::last_announced_on.ne(today)))
+      .select(CustomEvent::as_select())
+      .load(conn)
+}
+
+pub fn mark_custom_events_announced(conn: &mut SqliteConnection, event_ids: Vec<i32>, today: NaiveDate) -> Result<(), Error> {
+  diesel::update(custom_events::table.filter(custom_events::id.eq_any(event_ids)))
+      .set(custom_events::last_announced_on.eq(today))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+// ANNIVERSARIES
+pub fn insert_anniversary_opt_out(conn: &mut SqliteConnection, user: i64, guild_id: i64) -> Result<(), Error> {
+  diesel::insert_into(anniversary_opt_outs::table)
+      .values((anniversary_opt_outs::user_id.eq(user), anniversary_opt_outs::guild_id.eq(guild_id)))
+      .on_conflict_do_nothing()
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn delete_anniversary_opt_out(conn: &mut SqliteConnection, user: i64, guild_id: i64) -> Result<(), Error> {
+  diesel::delete(anniversary_opt_outs::table
+      .filter(anniversary_opt_outs::user_id.eq(user))
+      .filter(anniversary_opt_outs::guild_id.eq(guild_id)))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn list_anniversary_opt_outs(conn: &mut SqliteConnection, guild_id: i64) -> Result<Vec<i64>, Error> {
+  anniversary_opt_outs::table
+      .filter(anniversary_opt_outs::guild_id.eq(guild_id))
+      .select(anniversary_opt_outs::user_id)
+      .load(conn)
+}
+
+/// Guilds with an anniversary channel that haven't had today's anniversaries announced yet.
+pub fn list_pending_anniversary_guilds(conn: &mut SqliteConnection, today: NaiveDate) -> Result<Vec<GuildSettings>, Error> {
+  guild_settings::table
+      .filter(guild_settings::anniversary_channel_id.is_not_null())
+      .filter(guild_settings::anniversaries_announced_on.is_null()
+          .or(guild_settings::anniversaries_announced_on.ne(today)))
+      .select(GuildSettings::as_select())
+      .load(conn)
+}
+
+pub fn mark_anniversaries_announced(conn: &mut SqliteConnection, guild_id: i64, today: NaiveDate) -> Result<(), Error> {
+  diesel::update(guild_settings::table.filter(guild_settings::guild_id.eq(guild_id)))
+      .set(guild_settings::anniversaries_announced_on.eq(today))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+// CAKE DAYS
+/// Guilds with cake days enabled and an announcement channel that haven't been checked today.
+pub fn list_pending_cake_day_guilds(conn: &mut SqliteConnection, today: NaiveDate) -> Result<Vec<GuildSettings>, Error> {
+  guild_settings::table
+      .filter(guild_settings::announcements_channel_id.is_not_null())
+      .filter(guild_settings::guild_cake_day.eq(true).or(guild_settings::bot_cake_day.eq(true)))
+      .filter(guild_settings::cake_days_announced_on.is_null()
+          .or(guild_settings::cake_days_announced_on.ne(today)))
+      .select(GuildSettings::as_select())
+      .load(conn)
+}
+
+pub fn mark_cake_days_announced(conn: &mut SqliteConnection, guild_id: i64, today: NaiveDate) -> Result<(), Error> {
+  diesel::update(guild_settings::table.filter(guild_settings::guild_id.eq(guild_id)))
+      .set(guild_settings::cake_days_announced_on.eq(today))
+      .execute(conn)?;
+
+  Ok(())
+}
+
 // GUILD SETTINGS
 pub fn insert_guild_settings(conn: &mut SqliteConnection, guild_id: i64, announcements_channel_id: Option<i64>) -> Result<(), Error> {
   let new_guild_settings = NewGuildSettings {
@@ -129,3 +690,374 @@ pub fn get_announcement_channel(conn: &mut SqliteConnection, guild_id: i64) -> R
     Err(e) => Err(e),
   }
 }
+
+pub fn set_birthday_board(conn: &mut SqliteConnection, guild_id: i64, board: Option<(i64, i64)>) -> Result<(), Error> {
+  let (channel_id, message_id) = match board {
+    Some((channel_id, message_id)) => (Some(channel_id), Some(message_id)),
+    None => (None, None),
+  };
+
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::board_channel_id.eq(channel_id),
+        guild_settings::board_message_id.eq(message_id),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::board_channel_id.eq(channel_id),
+        guild_settings::board_message_id.eq(message_id),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn get_birthday_board(conn: &mut SqliteConnection, guild_id: i64) -> Result<Option<(i64, i64)>, Error> {
+  let board = guild_settings::table
+      .filter(guild_settings::guild_id.eq(guild_id))
+      .select((guild_settings::board_channel_id, guild_settings::board_message_id))
+      .first::<(Option<i64>, Option<i64>)>(conn)
+      .optional()?;
+
+  match board {
+    Some((Some(channel_id), Some(message_id))) => Ok(Some((channel_id, message_id))),
+    _ => Ok(None),
+  }
+}
+
+pub fn get_guild_settings(conn: &mut SqliteConnection, guild_id: i64) -> Result<Option<GuildSettings>, Error> {
+  let settings = guild_settings::table
+      .filter(guild_settings::guild_id.eq(guild_id))
+      .select(GuildSettings::as_select())
+      .first(conn)
+      .optional()?;
+
+  Ok(settings)
+}
+
+pub fn set_birthday_calendar(conn: &mut SqliteConnection, guild_id: i64, channel_id: i64, size: i32) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::calendar_channel_id.eq(channel_id),
+        guild_settings::calendar_message_id.eq(None::<i64>),
+        guild_settings::calendar_size.eq(size),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::calendar_channel_id.eq(channel_id),
+        guild_settings::calendar_message_id.eq(None::<i64>),
+        guild_settings::calendar_size.eq(size),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn update_calendar_message(conn: &mut SqliteConnection, guild_id: i64, message_id: i64) -> Result<(), Error> {
+  diesel::update(guild_settings::table.filter(guild_settings::guild_id.eq(guild_id)))
+      .set(guild_settings::calendar_message_id.eq(message_id))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+/// Guilds with a birthday calendar or board posted.
+pub fn list_birthday_view_guilds(conn: &mut SqliteConnection) -> Result<Vec<i64>, Error> {
+  let guild_ids = guild_settings::table
+      .filter(guild_settings::calendar_channel_id.is_not_null().or(guild_settings::board_message_id.is_not_null()))
+      .select(guild_settings::guild_id)
+      .load(conn)?;
+
+  Ok(guild_ids)
+}
+
+pub fn set_calendar_style(conn: &mut SqliteConnection, guild_id: i64, theme: &str, font: &str) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::calendar_theme.eq(theme),
+        guild_settings::calendar_font.eq(font),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::calendar_theme.eq(theme),
+        guild_settings::calendar_font.eq(font),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_birthday_approval(conn: &mut SqliteConnection, guild_id: i64, required: bool, moderation_channel_id: Option<i64>) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::require_birthday_approval.eq(required),
+        guild_settings::moderation_channel_id.eq(moderation_channel_id),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::require_birthday_approval.eq(required),
+        guild_settings::moderation_channel_id.eq(moderation_channel_id),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_birthday_change_limits(conn: &mut SqliteConnection, guild_id: i64, cooldown_hours: i32, changes_per_year: i32) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::birthday_change_cooldown_hours.eq(cooldown_hours),
+        guild_settings::birthday_changes_per_year.eq(changes_per_year),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::birthday_change_cooldown_hours.eq(cooldown_hours),
+        guild_settings::birthday_changes_per_year.eq(changes_per_year),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_announcement_threads(
+  conn: &mut SqliteConnection,
+  guild_id: i64,
+  enabled: bool,
+  archive_minutes: i32,
+  reactions: Option<&str>,
+) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::announcement_threads.eq(enabled),
+        guild_settings::thread_archive_minutes.eq(archive_minutes),
+        guild_settings::announcement_reactions.eq(reactions),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::announcement_threads.eq(enabled),
+        guild_settings::thread_archive_minutes.eq(archive_minutes),
+        guild_settings::announcement_reactions.eq(reactions),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+// AUDIT LOG
+pub fn insert_audit_entry(conn: &mut SqliteConnection, entry: &NewAuditEntry) -> Result<(), Error> {
+  diesel::insert_into(audit_log::table)
+      .values(entry)
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn list_audit_entries(conn: &mut SqliteConnection, guild_id: i64, limit: i64) -> Result<Vec<AuditEntry>, Error> {
+  audit_log::table
+      .filter(audit_log::guild_id.eq(guild_id))
+      .order((audit_log::created_at.desc(), audit_log::id.desc()))
+      .limit(limit)
+      .select(AuditEntry::as_select())
+      .load(conn)
+}
+
+pub fn count_audit_entries_since(conn: &mut SqliteConnection, guild_id: i64, action: &str, since: NaiveDateTime) -> Result<i64, Error> {
+  audit_log::table
+      .filter(audit_log::guild_id.eq(guild_id))
+      .filter(audit_log::action.eq(action))
+      .filter(audit_log::created_at.ge(since))
+      .count()
+      .get_result(conn)
+}
+
+/// Like [`count_audit_entries_since`], but skips entries that removed a value, e.g. color resets.
+pub fn count_audit_entries_with_value_since(conn: &mut SqliteConnection, guild_id: i64, action: &str, since: NaiveDateTime) -> Result<i64, Error> {
+  audit_log::table
+      .filter(audit_log::guild_id.eq(guild_id))
+      .filter(audit_log::action.eq(action))
+      .filter(audit_log::new_value.is_not_null())
+      .filter(audit_log::created_at.ge(since))
+      .count()
+      .get_result(conn)
+}
+
+pub fn set_audit_channel(conn: &mut SqliteConnection, guild_id: i64, channel_id: Option<i64>) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::audit_channel_id.eq(channel_id),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::audit_channel_id.eq(channel_id))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_wish_delivery(conn: &mut SqliteConnection, guild_id: i64, by_dm: bool) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::deliver_wishes_by_dm.eq(by_dm),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::deliver_wishes_by_dm.eq(by_dm))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_anniversary_channel(conn: &mut SqliteConnection, guild_id: i64, channel_id: Option<i64>) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::anniversary_channel_id.eq(channel_id),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::anniversary_channel_id.eq(channel_id))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_anniversary_template(conn: &mut SqliteConnection, guild_id: i64, template: Option<&str>) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::anniversary_template.eq(template),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::anniversary_template.eq(template))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_cake_days(conn: &mut SqliteConnection, guild_id: i64, guild_cake_day: bool, bot_cake_day: bool) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::guild_cake_day.eq(guild_cake_day),
+        guild_settings::bot_cake_day.eq(bot_cake_day),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::guild_cake_day.eq(guild_cake_day),
+        guild_settings::bot_cake_day.eq(bot_cake_day),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_birthday_info_extras(conn: &mut SqliteConnection, guild_id: i64, enabled: bool) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::birthday_info_extras.eq(enabled),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::birthday_info_extras.eq(enabled))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_color_contrast_policy(conn: &mut SqliteConnection, guild_id: i64, policy: &str, threshold: f64) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::color_contrast_policy.eq(policy),
+        guild_settings::color_contrast_threshold.eq(threshold),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set((
+        guild_settings::color_contrast_policy.eq(policy),
+        guild_settings::color_contrast_threshold.eq(threshold),
+      ))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_palette_only(conn: &mut SqliteConnection, guild_id: i64, enabled: bool) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::palette_only.eq(enabled),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::palette_only.eq(enabled))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn list_color_role_cleanup_guilds(conn: &mut SqliteConnection) -> Result<Vec<i64>, Error> {
+  guild_settings::table
+      .filter(guild_settings::color_role_cleanup.eq(true))
+      .select(guild_settings::guild_id)
+      .load(conn)
+}
+
+pub fn set_color_role_cleanup(conn: &mut SqliteConnection, guild_id: i64, enabled: bool) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::color_role_cleanup.eq(enabled),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::color_role_cleanup.eq(enabled))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_color_role_anchor(conn: &mut SqliteConnection, guild_id: i64, role_id: Option<i64>) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::color_role_anchor_id.eq(role_id),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::color_role_anchor_id.eq(role_id))
+      .execute(conn)?;
+
+  Ok(())
+}
+
+pub fn set_shared_color_roles(conn: &mut SqliteConnection, guild_id: i64, enabled: bool) -> Result<(), Error> {
+  diesel::insert_into(guild_settings::table)
+      .values((
+        guild_settings::guild_id.eq(guild_id),
+        guild_settings::shared_color_roles.eq(enabled),
+      ))
+      .on_conflict(guild_settings::guild_id)
+      .do_update()
+      .set(guild_settings::shared_color_roles.eq(enabled))
+      .execute(conn)?;
+
+  Ok(())
+}
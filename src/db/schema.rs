@@ -1,5 +1,56 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    anniversary_opt_outs (id) {
+        id -> Integer,
+        user_id -> BigInt,
+        guild_id -> BigInt,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        actor_id -> BigInt,
+        target_id -> BigInt,
+        action -> Text,
+        old_value -> Nullable<Text>,
+        new_value -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    birthday_announcements (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        user_id -> BigInt,
+        announced_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    birthday_changes (id) {
+        id -> Integer,
+        user_id -> BigInt,
+        guild_id -> BigInt,
+        changed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    birthday_wishes (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        author_id -> BigInt,
+        recipient_id -> BigInt,
+        message -> Text,
+        created_at -> Timestamp,
+        delivered -> Bool,
+    }
+}
+
 diesel::table! {
     birthdays (id) {
         id -> Integer,
@@ -7,6 +58,58 @@ diesel::table! {
         guild_id -> BigInt,
         date -> Date,
         announced_this_year -> Bool,
+        approved -> Bool,
+        pending_date -> Nullable<Date>,
+    }
+}
+
+diesel::table! {
+    color_history (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        user_id -> BigInt,
+        color -> Text,
+        changed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    color_palette (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        name -> Text,
+        color -> Text,
+    }
+}
+
+diesel::table! {
+    color_role_holders (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        user_id -> BigInt,
+        role_id -> BigInt,
+    }
+}
+
+diesel::table! {
+    color_roles (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        role_id -> BigInt,
+        color -> Text,
+    }
+}
+
+diesel::table! {
+    custom_events (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        name -> Text,
+        date -> Date,
+        recurrence -> Text,
+        role_id -> Nullable<BigInt>,
+        created_by -> BigInt,
+        last_announced_on -> Nullable<Date>,
     }
 }
 
@@ -15,10 +118,59 @@ diesel::table! {
         id -> Integer,
         guild_id -> BigInt,
         announcements_channel_id -> Nullable<BigInt>,
+        board_channel_id -> Nullable<BigInt>,
+        board_message_id -> Nullable<BigInt>,
+        calendar_channel_id -> Nullable<BigInt>,
+        calendar_message_id -> Nullable<BigInt>,
+        calendar_size -> Integer,
+        calendar_theme -> Text,
+        calendar_font -> Text,
+        require_birthday_approval -> Bool,
+        moderation_channel_id -> Nullable<BigInt>,
+        birthday_change_cooldown_hours -> Integer,
+        birthday_changes_per_year -> Integer,
+        audit_channel_id -> Nullable<BigInt>,
+        announcement_threads -> Bool,
+        thread_archive_minutes -> Integer,
+        announcement_reactions -> Nullable<Text>,
+        deliver_wishes_by_dm -> Bool,
+        anniversary_channel_id -> Nullable<BigInt>,
+        anniversary_template -> Nullable<Text>,
+        anniversaries_announced_on -> Nullable<Date>,
+        guild_cake_day -> Bool,
+        bot_cake_day -> Bool,
+        cake_days_announced_on -> Nullable<Date>,
+        birthday_info_extras -> Bool,
+        color_contrast_policy -> Text,
+        color_contrast_threshold -> Double,
+        palette_only -> Bool,
+        shared_color_roles -> Bool,
+        color_role_cleanup -> Bool,
+        color_role_anchor_id -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    personal_color_roles (id) {
+        id -> Integer,
+        guild_id -> BigInt,
+        user_id -> BigInt,
+        role_id -> BigInt,
     }
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    anniversary_opt_outs,
+    audit_log,
+    birthday_announcements,
+    birthday_changes,
+    birthday_wishes,
     birthdays,
+    color_history,
+    color_palette,
+    color_role_holders,
+    color_roles,
+    custom_events,
     guild_settings,
+    personal_color_roles,
 );
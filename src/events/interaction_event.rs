@@ -0,0 +1,132 @@
+use crate::db::connection::establish_connection;
+use crate::db::queries::{approve_birthday, get_birthday_by_id, list_birthdays, reject_birthday};
+use crate::utils::audit_utils::{log_audit_event, AuditAction, AuditEvent};
+use crate::utils::birthday_utils::{birthday_announced_this_year, create_birthday_board, parse_birthday_approval_id, parse_birthday_board_id, refresh_birthday_views, BIRTHDAY_APPROVAL_PREFIX, BIRTHDAY_BOARD_PREFIX, BIRTHDAY_LIST_PAGE_SIZE};
+use crate::utils::date_utils::format_date;
+use crate::utils::embed_utils::{create_birthday_reviewed_embed, create_error_embed};
+use crate::{Data, Error};
+use chrono::Utc;
+use log::error;
+use poise::serenity_prelude::{ComponentInteraction, Context, CreateInteractionResponse, CreateInteractionResponseMessage, FullEvent, Interaction, Permissions};
+use poise::FrameworkContext;
+
+pub async fn interaction_event_handler(
+  ctx: Context,
+  event: FullEvent,
+  _framework: FrameworkContext<'_, Data, Error>,
+) -> Result<(), Error> {
+  if let FullEvent::InteractionCreate { interaction: Interaction::Component(component) } = event {
+    if component.data.custom_id.starts_with(BIRTHDAY_BOARD_PREFIX) {
+      handle_birthday_board_press(&ctx, &component).await?;
+    } else if component.data.custom_id.starts_with(BIRTHDAY_APPROVAL_PREFIX) {
+      handle_birthday_approval_press(&ctx, &component).await?;
+    }
+  }
+  Ok(())
+}
+
+async fn handle_birthday_board_press(ctx: &Context, component: &ComponentInteraction) -> Result<(), Error> {
+  let Some(guild_id) = component.guild_id else {
+    return Ok(());
+  };
+
+  let conn = &mut establish_connection();
+  let birthdays = match list_birthdays(conn, i64::from(guild_id)) {
+    Ok(birthdays) => birthdays,
+    Err(e) => {
+      error!("Error loading birthday board for guild {}: {:?}", guild_id, e);
+      return Ok(());
+    }
+  };
+
+  let total_pages = birthdays.len().div_ceil(BIRTHDAY_LIST_PAGE_SIZE);
+  let Some(page) = parse_birthday_board_id(&component.data.custom_id, total_pages) else {
+    return Ok(());
+  };
+
+  let (embed, components) = create_birthday_board(birthdays, page);
+  component
+      .create_response(
+        ctx,
+        CreateInteractionResponse::UpdateMessage(
+          CreateInteractionResponseMessage::new()
+              .embed(embed)
+              .components(components),
+        ),
+      )
+      .await?;
+
+  Ok(())
+}
+
+async fn handle_birthday_approval_press(ctx: &Context, component: &ComponentInteraction) -> Result<(), Error> {
+  let Some((approve, birthday_id, date)) = parse_birthday_approval_id(&component.data.custom_id) else {
+    return Ok(());
+  };
+
+  let is_moderator = component.member
+      .as_ref()
+      .and_then(|member| member.permissions)
+      .is_some_and(|permissions| permissions.contains(Permissions::MANAGE_EVENTS));
+
+  if !is_moderator {
+    let embed = create_error_embed(
+      format!("You don't have the required **{}** permission.", Permissions::MANAGE_EVENTS),
+      "Make sure you have the required permissions".to_string(),
+    );
+
+    component
+        .create_response(ctx, CreateInteractionResponse::Message(
+          CreateInteractionResponseMessage::new().embed(embed).ephemeral(true),
+        ))
+        .await?;
+    return Ok(());
+  }
+
+  let conn = &mut establish_connection();
+  let birthday = get_birthday_by_id(conn, birthday_id).ok().flatten();
+  let reviewed = if approve {
+    // Members are announced once a year, however often they change or delete their birthday.
+    let announced = match &birthday {
+      Some(birthday) => birthday_announced_this_year(conn, birthday.guild_id, birthday.user_id, Utc::now().naive_utc()).unwrap_or_else(|e| {
+        error!("Error checking birthday announcements for user {}: {:?}", birthday.user_id, e);
+        false
+      }),
+      None => false,
+    };
+    approve_birthday(conn, birthday_id, date, announced)
+  } else {
+    reject_birthday(conn, birthday_id, date)
+  };
+
+  let embed = match (reviewed, birthday) {
+    (Ok(true), Some(birthday)) => {
+      refresh_birthday_views(&ctx.http, conn, birthday.guild_id).await;
+      log_audit_event(&ctx.http, conn, AuditEvent {
+        guild_id: birthday.guild_id,
+        actor_id: u64::from(component.user.id) as i64,
+        target_id: birthday.user_id,
+        action: if approve { AuditAction::BirthdayApprove } else { AuditAction::BirthdayReject },
+        old_value: None,
+        new_value: Some(date.to_string()),
+      }).await;
+      create_birthday_reviewed_embed(birthday.user_id, format_date(date), approve, component.user.id)
+    }
+    (Ok(_), _) => create_error_embed(
+      "This birthday is no longer pending.".to_string(),
+      "It was changed or already reviewed.".to_string(),
+    ),
+    (Err(e), _) => create_error_embed(
+      format!("Error while reviewing the birthday: {}", e),
+      "Please try again later.".to_string(),
+    ),
+  };
+
+  component
+      .create_response(ctx, CreateInteractionResponse::UpdateMessage(
+        CreateInteractionResponseMessage::new().embed(embed).components(vec![]),
+      ))
+      .await?;
+
+  Ok(())
+}
@@ -1 +1,2 @@
 pub mod login_event;
+pub mod interaction_event;
@@ -8,17 +8,21 @@ mod db;
 pub mod scheduler;
 
 use crate::db::connection::establish_connection;
+use crate::events::interaction_event::interaction_event_handler;
 use crate::events::login_event::login_event_handler;
+use crate::utils::avatar_utils::{HttpImageFetcher, ImageFetcher};
 use dotenv::var;
 use log::error;
-use poise::serenity_prelude::{ClientBuilder, Error, GatewayIntents, Http};
+use poise::serenity_prelude::{ClientBuilder, Error, GatewayIntents, Http, HttpBuilder};
 use scheduler::start_scheduler;
 use structured_logger::{Builder, json};
 use std::{fs::{self, OpenOptions}, path::Path, sync::Arc};
 use tokio::sync::Mutex;
 
 type Context<'a> = poise::Context<'a, Data, Error>;
-pub struct Data {}
+pub struct Data {
+  pub image_fetcher: Arc<dyn ImageFetcher>,
+}
 
 #[tokio::main]
 async fn main() {
@@ -26,19 +30,25 @@ async fn main() {
 
   let token = var("DISCORD_TOKEN").expect("Missing DISCORD_TOKEN in .env file.");
   let intents = GatewayIntents::non_privileged() | GatewayIntents::GUILD_MEMBERS;
+  let http_client = reqwest::Client::new();
+  let http = HttpBuilder::new(&token).client(http_client.clone()).build();
 
   let options = poise::FrameworkOptions {
     commands: vec![
       commands::color::color(),
+      commands::color_palette::colorpalette(),
       commands::birthday::birthday(),
       commands::set_channel::setchannel(),
+      commands::settings::settings(),
+      commands::audit::audit(),
+      commands::anniversary::anniversary(),
+      commands::event::event(),
     ],
     event_handler: |ctx, event, framework, _| {
-      Box::pin(login_event_handler(
-        ctx.clone(),
-        event.clone(),
-        framework,
-      ))
+      Box::pin(async move {
+        login_event_handler(ctx.clone(), event.clone(), framework).await?;
+        interaction_event_handler(ctx.clone(), event.clone(), framework).await
+      })
     },
     ..Default::default()
   };
@@ -47,13 +57,13 @@ async fn main() {
       .setup(move |ctx, _ready, framework| {
         Box::pin(async move {
           poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-          Ok(Data {})
+          Ok(Data { image_fetcher: Arc::new(HttpImageFetcher::new(http_client)) })
         })
       })
       .options(options)
       .build();
 
-  let mut client = ClientBuilder::new(token, intents)
+  let mut client = ClientBuilder::new_with_http(http, intents)
       .framework(framework)
       .await
       .expect("Failed to build Client.");
@@ -1,4 +1,8 @@
-use crate::utils::birthday_utils::handle_birthday_announcements;
+use crate::utils::anniversary_utils::handle_anniversary_announcements;
+use crate::utils::birthday_utils::{handle_birthday_announcements, refresh_all_birthday_views};
+use crate::utils::cake_day_utils::handle_cake_day_announcements;
+use crate::utils::color_role_utils::handle_color_role_cleanup;
+use crate::utils::event_utils::handle_custom_event_announcements;
 use diesel::SqliteConnection;
 use log::error;
 use poise::serenity_prelude::Http;
@@ -9,18 +13,77 @@ use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
 pub async fn start_scheduler(http: Arc<Http>, db_pool: Arc<Mutex<SqliteConnection>>) -> Result<(), JobSchedulerError> {
   let scheduler = JobScheduler::new().await?;
 
+  let calendar_task = Job::new("0 0 0 * * *", {
+    let http = http.clone();
+    let db_pool = db_pool.clone();
+
+    move |_uuid, _l| {
+      let http = http.clone();
+      let db_pool = db_pool.clone();
+
+      tokio::spawn(async move {
+        if let Err(e) = refresh_all_birthday_views(&http, db_pool).await {
+          error!("Error during birthday board and calendar refresh: {:?}", e);
+        }
+      });
+    }
+  })?;
+
+  let cleanup_task = Job::new("0 0 4 * * *", {
+    let http = http.clone();
+    let db_pool = db_pool.clone();
+
+    move |_uuid, _l| {
+      let http = http.clone();
+      let db_pool = db_pool.clone();
+
+      tokio::spawn(async move {
+        if let Err(e) = handle_color_role_cleanup(&http, db_pool).await {
+          error!("Error during color role cleanup: {:?}", e);
+        }
+      });
+    }
+  })?;
+
+  let anniversary_task = Job::new("0 30 * * * *", {
+    let http = http.clone();
+    let db_pool = db_pool.clone();
+
+    move |_uuid, _l| {
+      let http = http.clone();
+      let db_pool = db_pool.clone();
+
+      tokio::spawn(async move {
+        if let Err(e) = handle_anniversary_announcements(&http, db_pool.clone()).await {
+          error!("Error during anniversary announcement: {:?}", e);
+        }
+
+        if let Err(e) = handle_cake_day_announcements(&http, db_pool).await {
+          error!("Error during cake day announcement: {:?}", e);
+        }
+      });
+    }
+  })?;
+
   let task = Job::new("0 0 * * * *", move |_uuid, _l| {
     let http = http.clone();
     let db_pool = db_pool.clone();
 
     tokio::spawn(async move {
-      if let Err(e) = handle_birthday_announcements(&http, db_pool).await {
+      if let Err(e) = handle_birthday_announcements(&http, db_pool.clone()).await {
         error!("Error during birthday announcement: {:?}", e);
       }
+
+      if let Err(e) = handle_custom_event_announcements(&http, db_pool).await {
+        error!("Error during custom event announcement: {:?}", e);
+      }
     });
   })?;
 
   scheduler.add(task).await?;
+  scheduler.add(calendar_task).await?;
+  scheduler.add(anniversary_task).await?;
+  scheduler.add(cleanup_task).await?;
   scheduler.start().await?;
 
   Ok(())
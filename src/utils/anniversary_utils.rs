@@ -0,0 +1,141 @@
+use crate::db::queries::{list_anniversary_opt_outs, list_pending_anniversary_guilds, mark_anniversaries_announced};
+use crate::utils::birthday_utils::group_by_guild;
+use crate::utils::date_utils::anniversary_years;
+use crate::utils::embed_utils::{chunk_lines, create_anniversary_embed, EMBED_DESCRIPTION_LIMIT};
+use crate::utils::user_utils::fetch_all_members;
+use chrono::{DateTime, Local, NaiveDate};
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::{ChannelId, CreateMessage, GuildId, Http, Member};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Leaves room for the "Anniversaries Today" heading in the description.
+const ANNIVERSARY_CHUNK_LIMIT: usize = EMBED_DESCRIPTION_LIMIT - 100;
+
+pub const DEFAULT_ANNIVERSARY_TEMPLATE: &str = "{user} joined **{years}** year(s) ago today!";
+
+pub struct Anniversary {
+  pub guild_id: i64,
+  pub user_id: i64,
+  pub years: i32,
+}
+
+pub async fn handle_anniversary_announcements(http: &Arc<Http>, db_pool: Arc<Mutex<SqliteConnection>>) -> Result<(), Box<dyn std::error::Error>> {
+  let mut conn = db_pool.lock().await;
+  let today = Local::now().naive_utc().date();
+
+  let guilds = list_pending_anniversary_guilds(&mut conn, today)?;
+  if guilds.is_empty() {
+    return Ok(());
+  }
+
+  let mut anniversaries = Vec::new();
+  let mut failed_guilds = Vec::new();
+  for settings in &guilds {
+    let opt_outs = list_anniversary_opt_outs(&mut conn, settings.guild_id)?;
+
+    match get_anniversaries_today(http, settings.guild_id, today).await {
+      Ok(found) => anniversaries.extend(found.into_iter().filter(|anniversary| !opt_outs.contains(&anniversary.user_id))),
+      Err(e) => {
+        error!("Error fetching members for guild {}: {:?}", settings.guild_id, e);
+        failed_guilds.push(settings.guild_id);
+      }
+    }
+  }
+
+  let mut anniversaries_by_guild = group_by_guild(anniversaries, |anniversary| anniversary.guild_id);
+
+  for settings in guilds {
+    let guild_id = settings.guild_id;
+
+    // The members couldn't be fetched, so leave the day unannounced and try again on the next run.
+    if failed_guilds.contains(&guild_id) {
+      continue;
+    }
+
+    let entries = anniversaries_by_guild.remove(&guild_id).unwrap_or_default();
+
+    if !entries.is_empty() {
+      let Some(channel_id) = settings.anniversary_channel_id else {
+        continue;
+      };
+      let template = settings.anniversary_template.as_deref().unwrap_or(DEFAULT_ANNIVERSARY_TEMPLATE);
+
+      // Nothing was sent, so leave the day unannounced and try again on the next run.
+      if let Err(e) = announce_anniversaries(http, channel_id, template, &entries).await {
+        error!("Error announcing anniversaries for guild {}: {:?}", guild_id, e);
+        continue;
+      }
+    }
+
+    if let Err(e) = mark_anniversaries_announced(&mut conn, guild_id, today) {
+      error!("Error marking anniversaries announced for guild {}: {:?}", guild_id, e);
+    }
+  }
+
+  Ok(())
+}
+
+/// Returns everyone (except bots) who joined on this day in an earlier year.
+async fn get_anniversaries_today(http: &Http, guild_id: i64, today: NaiveDate) -> Result<Vec<Anniversary>, poise::serenity_prelude::Error> {
+  let members = fetch_all_members(http, GuildId::new(guild_id as u64)).await?;
+
+  let anniversaries = members
+      .iter()
+      .filter(|member| !member.user.bot)
+      .filter_map(|member| {
+        let years = joined_date(member).and_then(|joined| anniversary_years(joined, today))?;
+
+        Some(Anniversary {
+          guild_id,
+          user_id: i64::from(member.user.id),
+          years,
+        })
+      })
+      .collect();
+
+  Ok(anniversaries)
+}
+
+fn joined_date(member: &Member) -> Option<NaiveDate> {
+  let joined_at = member.joined_at?;
+
+  DateTime::from_timestamp(joined_at.unix_timestamp(), 0).map(|joined| joined.date_naive())
+}
+
+pub fn format_anniversary(template: &str, anniversary: &Anniversary) -> String {
+  template
+      .replace("{user}", &format!("<@{}>", anniversary.user_id))
+      .replace("{years}", &anniversary.years.to_string())
+}
+
+/// Sends the anniversaries, split over as many messages as needed.
+///
+/// Only fails if nothing could be sent; once the first message is out the day counts as announced,
+/// so a later failure doesn't repeat the earlier messages on the next run.
+async fn announce_anniversaries(
+  http: &Http,
+  channel_id: i64,
+  template: &str,
+  entries: &[Anniversary],
+) -> Result<(), poise::serenity_prelude::Error> {
+  let lines = entries
+      .iter()
+      .map(|anniversary| format_anniversary(template, anniversary))
+      .collect::<Vec<String>>();
+  let channel_id = ChannelId::new(channel_id as u64);
+
+  for (index, chunk) in chunk_lines(&lines, ANNIVERSARY_CHUNK_LIMIT).into_iter().enumerate() {
+    match channel_id.send_message(http, CreateMessage::new().embed(create_anniversary_embed(chunk))).await {
+      Ok(_) => {}
+      Err(e) if index == 0 => return Err(e),
+      Err(e) => {
+        error!("Error sending anniversary message {} to channel {}: {:?}", index + 1, channel_id, e);
+        break;
+      }
+    }
+  }
+
+  Ok(())
+}
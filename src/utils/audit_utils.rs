@@ -0,0 +1,110 @@
+use crate::db::models::{AuditEntry, NewAuditEntry};
+use crate::db::queries::{get_guild_settings, insert_audit_entry};
+use crate::utils::embed_utils::create_audit_embed;
+use chrono::Utc;
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::{ChannelId, CreateMessage, Http};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditAction {
+  BirthdaySet,
+  BirthdayDelete,
+  BirthdayApprove,
+  BirthdayReject,
+  ColorChange,
+}
+
+impl AuditAction {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AuditAction::BirthdaySet => "birthday_set",
+      AuditAction::BirthdayDelete => "birthday_delete",
+      AuditAction::BirthdayApprove => "birthday_approve",
+      AuditAction::BirthdayReject => "birthday_reject",
+      AuditAction::ColorChange => "color_change",
+    }
+  }
+
+  pub fn parse(action: &str) -> Option<Self> {
+    match action {
+      "birthday_set" => Some(AuditAction::BirthdaySet),
+      "birthday_delete" => Some(AuditAction::BirthdayDelete),
+      "birthday_approve" => Some(AuditAction::BirthdayApprove),
+      "birthday_reject" => Some(AuditAction::BirthdayReject),
+      "color_change" => Some(AuditAction::ColorChange),
+      _ => None,
+    }
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      AuditAction::BirthdaySet => "🎂 Birthday set",
+      AuditAction::BirthdayDelete => "🗑 Birthday deleted",
+      AuditAction::BirthdayApprove => "✅ Birthday approved",
+      AuditAction::BirthdayReject => "🚫 Birthday rejected",
+      AuditAction::ColorChange => "🎨 Color changed",
+    }
+  }
+}
+
+pub struct AuditEvent {
+  pub guild_id: i64,
+  pub actor_id: i64,
+  pub target_id: i64,
+  pub action: AuditAction,
+  pub old_value: Option<String>,
+  pub new_value: Option<String>,
+}
+
+/// Records the action in the audit log and posts it to the guild's log channel, if set.
+///
+/// Failures are only logged, so auditing never breaks the command that triggered it.
+pub async fn log_audit_event(http: &Http, conn: &mut SqliteConnection, event: AuditEvent) {
+  let guild_id = event.guild_id;
+  let entry = NewAuditEntry {
+    guild_id,
+    actor_id: event.actor_id,
+    target_id: event.target_id,
+    action: event.action.as_str(),
+    old_value: event.old_value.as_deref(),
+    new_value: event.new_value.as_deref(),
+    created_at: Utc::now().naive_utc(),
+  };
+
+  if let Err(e) = insert_audit_entry(conn, &entry) {
+    error!("Error recording audit entry for guild {}: {:?}", guild_id, e);
+  }
+
+  let audit_channel_id = match get_guild_settings(conn, guild_id) {
+    Ok(settings) => settings.and_then(|settings| settings.audit_channel_id),
+    Err(e) => {
+      error!("Error fetching audit channel for guild {}: {:?}", guild_id, e);
+      None
+    }
+  };
+
+  if let Some(channel_id) = audit_channel_id {
+    let embed = create_audit_embed(&event);
+
+    if let Err(e) = ChannelId::new(channel_id as u64).send_message(http, CreateMessage::new().embed(embed)).await {
+      error!("Error posting audit entry to channel {} for guild {}: {:?}", channel_id, guild_id, e);
+    }
+  }
+}
+
+pub fn format_audit_entry(entry: &AuditEntry) -> String {
+  let label = AuditAction::parse(&entry.action)
+      .map(|action| action.label().to_string())
+      .unwrap_or_else(|| entry.action.clone());
+
+  format!(
+    "<t:{}:f> **{}** by <@{}> for <@{}>: {} → {}\n",
+    entry.created_at.and_utc().timestamp(),
+    label,
+    entry.actor_id,
+    entry.target_id,
+    entry.old_value.as_deref().unwrap_or("-"),
+    entry.new_value.as_deref().unwrap_or("-"),
+  )
+}
@@ -0,0 +1,90 @@
+use crate::utils::color_utils::{ColorUtils, Rgb};
+use image::{ImageFormat, RgbaImage};
+use poise::serenity_prelude::User;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Avatars are scaled down by Discord first; a few thousand pixels are plenty for median cut.
+const AVATAR_SIZE: u32 = 64;
+
+type FetchError = Box<dyn std::error::Error + Send + Sync>;
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, FetchError>> + Send + 'a>>;
+
+/// Downloads images, so tests can serve local fixtures instead of Discord's CDN.
+pub trait ImageFetcher: Send + Sync {
+  fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a>;
+}
+
+/// Reuses the bot's own HTTP client, so avatar downloads share its connection pool.
+pub struct HttpImageFetcher {
+  client: reqwest::Client,
+}
+
+impl HttpImageFetcher {
+  pub fn new(client: reqwest::Client) -> Self {
+    Self { client }
+  }
+}
+
+impl ImageFetcher for HttpImageFetcher {
+  fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a> {
+    Box::pin(async move {
+      let response = self.client.get(url).send().await?.error_for_status()?;
+
+      Ok(response.bytes().await?.to_vec())
+    })
+  }
+}
+
+/// The avatar as a small PNG, since that's the only format we decode.
+pub fn avatar_png_url(user: &User) -> String {
+  match &user.avatar {
+    Some(hash) => format!("https://cdn.discordapp.com/avatars/{}/{}.png?size={}", user.id, hash, AVATAR_SIZE),
+    None => user.default_avatar_url(),
+  }
+}
+
+/// Downloads the image and returns up to `count` of its dominant colors, most common first.
+pub async fn fetch_dominant_colors(fetcher: &dyn ImageFetcher, url: &str, count: usize) -> Result<Vec<Rgb>, FetchError> {
+  let bytes = fetcher.fetch(url).await?;
+  let image = image::load_from_memory_with_format(&bytes, ImageFormat::Png)?.to_rgba8();
+
+  Ok(ColorUtils::dominant_colors(&opaque_pixels(&image), count))
+}
+
+/// Transparent corners of round avatars would otherwise count as black.
+fn opaque_pixels(image: &RgbaImage) -> Vec<Rgb> {
+  image
+      .pixels()
+      .filter(|pixel| pixel[3] >= 128)
+      .map(|pixel| Rgb::new(pixel[0], pixel[1], pixel[2]))
+      .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// An 8x8 avatar with transparent corners, mostly red with a blue stripe.
+  const AVATAR_FIXTURE: &[u8] = include_bytes!("../../assets/tests/avatar.png");
+
+  struct FixtureFetcher(&'static [u8]);
+
+  impl ImageFetcher for FixtureFetcher {
+    fn fetch<'a>(&'a self, _url: &'a str) -> FetchFuture<'a> {
+      Box::pin(async move { Ok(self.0.to_vec()) })
+    }
+  }
+
+  #[tokio::test]
+  async fn fetch_dominant_colors_ignores_transparent_corners() {
+    let colors = fetch_dominant_colors(&FixtureFetcher(AVATAR_FIXTURE), "avatar.png", 5).await.unwrap();
+
+    assert_eq!(colors, vec![Rgb::new(0xe0, 0x20, 0x20), Rgb::new(0x20, 0x40, 0xe0)]);
+  }
+
+  #[tokio::test]
+  async fn fetch_dominant_colors_rejects_other_formats() {
+    assert!(fetch_dominant_colors(&FixtureFetcher(b"GIF89a"), "avatar.gif", 3).await.is_err());
+  }
+}
@@ -1,10 +1,11 @@
-use crate::db::models::Birthday;
-use crate::db::queries::{get_announcement_channel, get_birthdays_today, reset_announced_flags, update_announced_value};
-use crate::utils::date_utils::{calculate_age, days_until_next_birthday, format_announcment_date};
-use crate::utils::embed_utils::create_birthday_embed;
+use crate::db::models::{Birthday, GroupedCount, GuildSettings};
+use crate::db::queries::{count_birthday_changes_since, count_birthdays, count_user_birthday_announcements_since, count_birthdays_by_month, count_birthdays_by_weekday, get_announcement_channel, get_average_age, get_birthday, get_birthday_board, get_birthdays_today, get_guild_settings, get_last_birthday_change, list_birthday_view_guilds, list_birthdays, list_pending_birthday_wishes, mark_birthday_wishes_delivered, record_birthday_announcements, reset_announced_flags, update_announced_value, update_calendar_message};
+use crate::utils::date_utils::{calculate_age, days_until_next_birthday, format_announcment_date, format_birthday_with_age, format_date, format_date_without_year};
+use crate::utils::embed_utils::{create_birthday_approval_embed, create_birthday_board_embed, create_birthday_calendar_embed, create_birthday_embed, create_birthday_wishes_embed, create_empty_birthday_embed};
 use diesel::SqliteConnection;
 use log::error;
-use poise::serenity_prelude::{Channel, ChannelId, CreateMessage, Http};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use poise::serenity_prelude::{AutoArchiveDuration, ButtonStyle, Channel, ChannelId, CreateActionRow, CreateButton, CreateEmbed, CreateMessage, CreateThread, EditMessage, GuildChannel, GuildId, Http, Message, MessageId, ReactionType, UserId, Error as SerenityError, StatusCode};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -20,9 +21,20 @@ pub async fn handle_birthday_announcements(http: &Arc<Http>, db_pool: Arc<Mutex<
   let birthdays_by_guild = group_birthdays_by_guild(birthdays);
 
   for (guild_id, birthday_entries) in birthdays_by_guild {
+    let celebrants = birthday_entries
+        .iter()
+        .map(|birthday| birthday.user_id)
+        .collect::<Vec<i64>>();
+
     if let Err(e) = announce_birthday_to_guild(http, &mut *conn, guild_id, birthday_entries).await {
       error!("Error announcing birthdays for guild {}: {:?}", guild_id, e);
     }
+
+    for user_id in celebrants {
+      if let Err(e) = deliver_birthday_wishes(http, &mut conn, guild_id, user_id).await {
+        error!("Error delivering birthday wishes to user {} in guild {}: {:?}", user_id, guild_id, e);
+      }
+    }
   }
 
   reset_announced_flags(&mut *conn)?;
@@ -31,13 +43,17 @@ pub async fn handle_birthday_announcements(http: &Arc<Http>, db_pool: Arc<Mutex<
 }
 
 pub fn group_birthdays_by_guild(birthdays: Vec<Birthday>) -> HashMap<i64, Vec<Birthday>> {
-  let mut birthdays_by_guild = HashMap::new();
-  for birthday in birthdays {
-    birthdays_by_guild.entry(birthday.guild_id)
+  group_by_guild(birthdays, |birthday| birthday.guild_id)
+}
+
+pub fn group_by_guild<T>(entries: Vec<T>, guild_of: impl Fn(&T) -> i64) -> HashMap<i64, Vec<T>> {
+  let mut entries_by_guild = HashMap::new();
+  for entry in entries {
+    entries_by_guild.entry(guild_of(&entry))
         .or_insert_with(Vec::new)
-        .push(birthday);
+        .push(entry);
   }
-  birthdays_by_guild
+  entries_by_guild
 }
 
 pub async fn announce_birthday_to_guild(
@@ -49,17 +65,39 @@ pub async fn announce_birthday_to_guild(
   if let Some(channel_id) = get_announcement_channel(conn, guild_id)? {
     match http.get_channel(ChannelId::from(channel_id as u64)).await {
       Ok(Channel::Guild(channel)) => {
-        let (user_mentions, _birthday_details) = get_birthday_details(&birthday_entries);
+        let embeds = get_birthday_details(&birthday_entries)
+            .iter()
+            .map(create_birthday_embed)
+            .collect::<Vec<CreateEmbed>>();
 
-        let embed = create_birthday_embed(user_mentions);
-        channel.send_message(http, CreateMessage::default().embed(embed)).await?;
+        // Discord allows up to 10 embeds per message; only the first one gets the reactions and thread.
+        let mut chunks = embeds.chunks(10);
+        let first_chunk = chunks.next().unwrap_or_default();
+        let message = channel.send_message(http, CreateMessage::default().embeds(first_chunk.to_vec())).await?;
 
+        // Once anything went out the day counts as announced, so a retry can't post it twice.
         let birthday_ids = birthday_entries
             .iter()
             .map(|birthday| birthday.id)
             .collect::<Vec<i32>>();
 
         update_announced_value(conn, birthday_ids)?;
+
+        let user_ids = birthday_entries.iter().map(|birthday| birthday.user_id).collect::<Vec<i64>>();
+        if let Err(e) = record_birthday_announcements(conn, guild_id, &user_ids, Utc::now().naive_utc()) {
+          error!("Error recording birthday announcements for guild {}: {:?}", guild_id, e);
+        }
+
+        for chunk in chunks {
+          if let Err(e) = channel.send_message(http, CreateMessage::default().embeds(chunk.to_vec())).await {
+            error!("Error sending the rest of the birthday announcement in guild {}: {:?}", guild_id, e);
+            break;
+          }
+        }
+
+        if let Some(settings) = get_guild_settings(conn, guild_id)? {
+          decorate_announcement(http, &channel, &message, &settings, &birthday_entries).await;
+        }
       }
       Ok(_) => {
         error!("Announcement channel {} is not a guild channel for guild {}", channel_id, guild_id);
@@ -75,26 +113,253 @@ pub async fn announce_birthday_to_guild(
   Ok(())
 }
 
-fn get_birthday_details(birthday_entries: &[Birthday]) -> (String, Vec<(String, i32)>) {
-  let mut user_mentions = String::new();
-  let mut birthday_details = Vec::new();
+/// Sends the celebrant every wish collected for them, by DM or in the announcement channel.
+pub async fn deliver_birthday_wishes(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: i64,
+  recipient_id: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+  let wishes = list_pending_birthday_wishes(conn, guild_id, recipient_id)?;
+  if wishes.is_empty() {
+    return Ok(());
+  }
+
+  let guild_name = GuildId::new(guild_id as u64)
+      .to_partial_guild(http)
+      .await
+      .map(|guild| guild.name)
+      .unwrap_or_else(|_| "the server".to_string());
+  let embed = create_birthday_wishes_embed(&guild_name, &wishes);
+
+  let settings = get_guild_settings(conn, guild_id)?;
+  let by_dm = settings.as_ref().is_none_or(|settings| settings.deliver_wishes_by_dm);
+  let delivered_by_dm = by_dm && UserId::new(recipient_id as u64)
+      .direct_message(http, CreateMessage::new().embed(embed.clone()))
+      .await
+      .is_ok();
+
+  if !delivered_by_dm {
+    let Some(channel_id) = settings.and_then(|settings| settings.announcements_channel_id) else {
+      return Err(format!("Announcement channel not set for guild {}", guild_id).into());
+    };
 
+    ChannelId::new(channel_id as u64)
+        .send_message(http, CreateMessage::new().content(format!("<@{}>", recipient_id)).embed(embed))
+        .await?;
+  }
+
+  mark_birthday_wishes_delivered(conn, wishes.iter().map(|wish| wish.id).collect())?;
+
+  Ok(())
+}
+
+/// Adds the guild's auto-reactions and discussion thread to a birthday announcement.
+async fn decorate_announcement(
+  http: &Http,
+  channel: &GuildChannel,
+  message: &Message,
+  settings: &GuildSettings,
+  birthday_entries: &[Birthday],
+) {
+  for emoji in settings.announcement_reactions.as_deref().unwrap_or_default().split_whitespace() {
+    let Ok(reaction) = ReactionType::try_from(emoji) else {
+      continue;
+    };
+
+    if let Err(e) = message.react(http, reaction).await {
+      error!("Error adding reaction {} to announcement in guild {}: {:?}", emoji, settings.guild_id, e);
+    }
+  }
+
+  if !settings.announcement_threads {
+    return;
+  }
+
+  let mut names = Vec::new();
   for birthday in birthday_entries {
-    let user_id = birthday.user_id;
-    let birthday_date = birthday.date;
-    let formatted_birthday = format_announcment_date(birthday_date);
-    let age = calculate_age(birthday_date);
+    match http.get_member(GuildId::new(birthday.guild_id as u64), UserId::new(birthday.user_id as u64)).await {
+      Ok(member) => names.push(member.display_name().to_string()),
+      Err(e) => error!("Error fetching member {} for announcement thread: {:?}", birthday.user_id, e),
+    }
+  }
+
+  let thread_name = create_thread_name(&names);
+  let thread = CreateThread::new(thread_name)
+      .auto_archive_duration(AutoArchiveDuration::from(settings.thread_archive_minutes as u16));
+
+  if let Err(e) = channel.create_thread_from_message(http, message.id, thread).await {
+    error!("Error creating announcement thread in guild {}: {:?}", settings.guild_id, e);
+  }
+}
+
+fn create_thread_name(names: &[String]) -> String {
+  let thread_name = match names {
+    [] => "🎉 Happy Birthday!".to_string(),
+    [name] => format!("🎉 Happy Birthday, {}!", name),
+    [rest @ .., last] => format!("🎉 Happy Birthday, {} & {}!", rest.join(", "), last),
+  };
+
+  // Discord limits thread names to 100 characters.
+  if thread_name.chars().count() > 100 {
+    format!("{}…", thread_name.chars().take(99).collect::<String>())
+  } else {
+    thread_name
+  }
+}
+
+/// Auto-archive durations Discord allows for threads.
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum ThreadArchive {
+  #[name = "1 hour"]
+  OneHour,
+  #[name = "1 day"]
+  OneDay,
+  #[name = "3 days"]
+  ThreeDays,
+  #[name = "1 week"]
+  OneWeek,
+}
+
+impl ThreadArchive {
+  pub fn minutes(&self) -> i32 {
+    match self {
+      ThreadArchive::OneHour => 60,
+      ThreadArchive::OneDay => 1440,
+      ThreadArchive::ThreeDays => 4320,
+      ThreadArchive::OneWeek => 10080,
+    }
+  }
+}
 
-    user_mentions.push_str(&format!("<@{}> ({} years old), ", user_id, age));
-    birthday_details.push((formatted_birthday, age));
+/// Everything the announcement says about one celebrant.
+pub struct CelebrantDetails {
+  pub user_id: i64,
+  pub age: i32,
+  pub milestone: Option<&'static str>,
+  /// Others celebrating on the same day.
+  pub shares_with: Vec<i64>,
+  /// Others born on the very same date, year included.
+  pub born_same_day: Vec<i64>,
+}
+
+/// Ages worth a special callout: coming of age and every round decade from 30.
+pub fn classify_milestone(age: i32) -> Option<&'static str> {
+  match age {
+    18 => Some("🎓 Officially an adult!"),
+    21 => Some("🥂 Twenty-one at last!"),
+    100 => Some("💯 A whole century!"),
+    age if age >= 30 && age % 10 == 0 => Some("🏅 A brand new decade!"),
+    _ => None,
   }
+}
+
+fn get_birthday_details(birthday_entries: &[Birthday]) -> Vec<CelebrantDetails> {
+  birthday_entries
+      .iter()
+      .map(|birthday| {
+        let age = calculate_age(birthday.date);
+        let others = birthday_entries
+            .iter()
+            .filter(|other| other.user_id != birthday.user_id);
+
+        CelebrantDetails {
+          user_id: birthday.user_id,
+          age,
+          milestone: classify_milestone(age),
+          shares_with: others
+              .clone()
+              .filter(|other| format_announcment_date(other.date) == format_announcment_date(birthday.date))
+              .map(|other| other.user_id)
+              .collect(),
+          born_same_day: others
+              .filter(|other| other.date == birthday.date)
+              .map(|other| other.user_id)
+              .collect(),
+        }
+      })
+      .collect()
+}
+
+pub const BIRTHDAY_BOARD_PREFIX: &str = "birthday_board";
+pub const BIRTHDAY_LIST_PAGE_SIZE: usize = 5;
 
-  if !user_mentions.is_empty() {
-    user_mentions.pop();
-    user_mentions.pop();
+pub fn create_birthday_list_pages(birthdays: &[Birthday]) -> Vec<String> {
+  birthdays
+      .chunks(BIRTHDAY_LIST_PAGE_SIZE)
+      .map(|chunk| {
+        chunk.iter().map(|birthday| {
+          let formatted_birthday = format_birthday_with_age(birthday);
+          format!("<@{}>: {} ({} years old)\n", birthday.user_id, formatted_birthday, calculate_age(birthday.date))
+        }).collect::<String>()
+      })
+      .collect()
+}
+
+/// Builds the birthday board message for the given page.
+///
+/// The current page is encoded in the buttons' custom ids
+/// (`birthday_board:<direction>:<page>`), so anyone can page the board
+/// and it keeps working after the bot restarts.
+pub fn create_birthday_board(mut birthdays: Vec<Birthday>, page: usize) -> (CreateEmbed, Vec<CreateActionRow>) {
+  if birthdays.is_empty() {
+    return (create_empty_birthday_embed(), vec![]);
   }
 
-  (user_mentions, birthday_details)
+  sort_birthdays_by_upcoming_date(&mut birthdays);
+
+  let pages = create_birthday_list_pages(&birthdays);
+  let page = page.min(pages.len() - 1);
+
+  let components = CreateActionRow::Buttons(vec![
+    CreateButton::new(format!("{}:prev:{}", BIRTHDAY_BOARD_PREFIX, page)).emoji('◀'),
+    CreateButton::new(format!("{}:next:{}", BIRTHDAY_BOARD_PREFIX, page)).emoji('▶'),
+  ]);
+
+  (create_birthday_board_embed(&pages[page], page, pages.len()), vec![components])
+}
+
+/// Resolves the page a birthday board button leads to.
+///
+/// Returns `None` if the custom id doesn't belong to the birthday board.
+pub fn parse_birthday_board_id(custom_id: &str, total_pages: usize) -> Option<usize> {
+  let mut parts = custom_id.split(':');
+
+  if parts.next()? != BIRTHDAY_BOARD_PREFIX {
+    return None;
+  }
+
+  let direction = parts.next()?;
+  let page = parts.next()?.parse::<usize>().ok()?;
+
+  if total_pages == 0 {
+    return Some(0);
+  }
+
+  let page = page.min(total_pages - 1);
+  match direction {
+    "next" => Some((page + 1) % total_pages),
+    "prev" => Some(page.checked_sub(1).unwrap_or(total_pages - 1)),
+    _ => None,
+  }
+}
+
+/// Re-renders the guild's birthday board (if one is posted) from its first page.
+pub async fn refresh_birthday_board(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+  if let Some((channel_id, message_id)) = get_birthday_board(conn, guild_id)? {
+    let birthdays = list_birthdays(conn, guild_id)?;
+    let (embed, components) = create_birthday_board(birthdays, 0);
+
+    ChannelId::new(channel_id as u64)
+        .edit_message(http, MessageId::new(message_id as u64), EditMessage::new().embed(embed).components(components))
+        .await?;
+  }
+
+  Ok(())
 }
 
 pub fn sort_birthdays_by_upcoming_date(birthdays: &mut Vec<Birthday>) {
@@ -104,3 +369,296 @@ pub fn sort_birthdays_by_upcoming_date(birthdays: &mut Vec<Birthday>) {
     days_until_a.cmp(&days_until_b)
   });
 }
+
+pub const BIRTHDAY_APPROVAL_PREFIX: &str = "birthday_approval";
+
+/// Posts a pending birthday to the moderation channel with approve and reject buttons.
+///
+/// The buttons carry the birthday id and date (`birthday_approval:<action>:<id>:<date>`),
+/// so a request is only applied to the submission it was posted for.
+pub async fn request_birthday_approval(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  moderation_channel_id: Option<i64>,
+  user_id: i64,
+  guild_id: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+  let Some(channel_id) = moderation_channel_id else {
+    return Err(format!("Moderation channel is not set for guild {}", guild_id).into());
+  };
+
+  let Some(birthday) = get_birthday(conn, user_id, guild_id)? else {
+    return Ok(());
+  };
+  let Some(pending_date) = birthday.pending_date else {
+    return Ok(());
+  };
+
+  let components = CreateActionRow::Buttons(vec![
+    CreateButton::new(format!("{}:approve:{}:{}", BIRTHDAY_APPROVAL_PREFIX, birthday.id, pending_date))
+        .label("Approve")
+        .style(ButtonStyle::Success),
+    CreateButton::new(format!("{}:reject:{}:{}", BIRTHDAY_APPROVAL_PREFIX, birthday.id, pending_date))
+        .label("Reject")
+        .style(ButtonStyle::Danger),
+  ]);
+
+  ChannelId::new(channel_id as u64)
+      .send_message(http, CreateMessage::new()
+          .embed(create_birthday_approval_embed(birthday.user_id, format_date(pending_date)))
+          .components(vec![components]))
+      .await?;
+
+  Ok(())
+}
+
+/// Parses an approval button id into (approve, birthday id, date).
+pub fn parse_birthday_approval_id(custom_id: &str) -> Option<(bool, i32, NaiveDate)> {
+  let mut parts = custom_id.split(':');
+
+  if parts.next()? != BIRTHDAY_APPROVAL_PREFIX {
+    return None;
+  }
+
+  let approve = match parts.next()? {
+    "approve" => true,
+    "reject" => false,
+    _ => return None,
+  };
+  let birthday_id = parts.next()?.parse::<i32>().ok()?;
+  let date = NaiveDate::parse_from_str(parts.next()?, "%Y-%m-%d").ok()?;
+
+  Some((approve, birthday_id, date))
+}
+
+/// Refreshes every birthday view of the guild (board and calendar), logging failures.
+pub async fn refresh_birthday_views(http: &Http, conn: &mut SqliteConnection, guild_id: i64) {
+  if let Err(e) = refresh_birthday_board(http, conn, guild_id).await {
+    error!("Error refreshing birthday board for guild {}: {:?}", guild_id, e);
+  }
+
+  if let Err(e) = refresh_birthday_calendar(http, conn, guild_id).await {
+    error!("Error refreshing birthday calendar for guild {}: {:?}", guild_id, e);
+  }
+}
+
+pub fn create_birthday_calendar(birthdays: &[Birthday]) -> String {
+  birthdays
+      .iter()
+      .map(|birthday| {
+        let days_until = days_until_next_birthday(birthday.date);
+        let when = match days_until {
+          0 => "**today** 🎉".to_string(),
+          1 => "tomorrow".to_string(),
+          _ => format!("in {} days", days_until),
+        };
+
+        format!("`{}` <@{}> ({})\n", format_date_without_year(birthday.date), birthday.user_id, when)
+      })
+      .collect()
+}
+
+/// Edits the guild's birthday calendar message in place, posting a new one if it was deleted.
+pub async fn refresh_birthday_calendar(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+  let Some(settings) = get_guild_settings(conn, guild_id)? else {
+    return Ok(());
+  };
+
+  let Some(channel_id) = settings.calendar_channel_id else {
+    return Ok(());
+  };
+
+  let mut birthdays = list_birthdays(conn, guild_id)?;
+  sort_birthdays_by_upcoming_date(&mut birthdays);
+  birthdays.truncate(settings.calendar_size.max(1) as usize);
+
+  let embed = if birthdays.is_empty() {
+    create_empty_birthday_embed()
+  } else {
+    create_birthday_calendar_embed(create_birthday_calendar(&birthdays))
+  };
+  let channel_id = ChannelId::new(channel_id as u64);
+
+  if let Some(message_id) = settings.calendar_message_id {
+    match channel_id.edit_message(http, MessageId::new(message_id as u64), EditMessage::new().embed(embed.clone())).await {
+      Ok(_) => return Ok(()),
+      Err(SerenityError::Http(e)) if e.status_code() == Some(StatusCode::NOT_FOUND) => {}
+      Err(e) => return Err(e.into()),
+    }
+  }
+
+  let message = channel_id.send_message(http, CreateMessage::new().embed(embed)).await?;
+  update_calendar_message(conn, guild_id, i64::from(message.id))?;
+
+  // Pinning needs Manage Messages; the calendar still works without it.
+  if let Err(e) = message.pin(http).await {
+    error!("Error pinning birthday calendar in guild {}: {:?}", guild_id, e);
+  }
+
+  Ok(())
+}
+
+/// Re-renders every board and calendar, since their order and "today" markers change with the date.
+pub async fn refresh_all_birthday_views(http: &Arc<Http>, db_pool: Arc<Mutex<SqliteConnection>>) -> Result<(), Box<dyn std::error::Error>> {
+  let mut conn = db_pool.lock().await;
+
+  for guild_id in list_birthday_view_guilds(&mut conn)? {
+    refresh_birthday_views(http, &mut conn, guild_id).await;
+  }
+
+  Ok(())
+}
+
+pub struct BirthdayStats {
+  pub registered: i64,
+  pub members: Option<u64>,
+  pub by_month: Vec<GroupedCount>,
+  pub by_weekday: Vec<GroupedCount>,
+  pub average_age: Option<f64>,
+  pub next_birthday: Option<Birthday>,
+}
+
+pub fn get_birthday_stats(conn: &mut SqliteConnection, guild_id: i64, members: Option<u64>) -> Result<BirthdayStats, diesel::result::Error> {
+  let mut birthdays = list_birthdays(conn, guild_id)?;
+  sort_birthdays_by_upcoming_date(&mut birthdays);
+
+  Ok(BirthdayStats {
+    registered: count_birthdays(conn, guild_id)?,
+    members,
+    by_month: count_birthdays_by_month(conn, guild_id)?,
+    by_weekday: count_birthdays_by_weekday(conn, guild_id)?,
+    average_age: get_average_age(conn, guild_id)?,
+    next_birthday: birthdays.into_iter().next(),
+  })
+}
+
+/// Checks the guild's cooldown and yearly cap for members changing their own birthday.
+///
+/// Returns the reason the change is refused, if any.
+pub fn check_birthday_change_limits(
+  conn: &mut SqliteConnection,
+  settings: Option<&GuildSettings>,
+  user_id: i64,
+  guild_id: i64,
+  now: NaiveDateTime,
+) -> Result<Option<String>, diesel::result::Error> {
+  let Some(settings) = settings else {
+    return Ok(None);
+  };
+
+  if settings.birthday_change_cooldown_hours > 0
+      && let Some(last_change) = get_last_birthday_change(conn, user_id, guild_id)? {
+    let available_at = last_change + Duration::hours(settings.birthday_change_cooldown_hours as i64);
+
+    if available_at > now {
+      return Ok(Some(format!("You can change your birthday again <t:{}:R>.", available_at.and_utc().timestamp())));
+    }
+  }
+
+  if settings.birthday_changes_per_year > 0
+      && count_birthday_changes_since(conn, user_id, guild_id, year_start(now))? >= settings.birthday_changes_per_year as i64 {
+    return Ok(Some(format!("You have used all {} birthday changes for this year.", settings.birthday_changes_per_year)));
+  }
+
+  Ok(None)
+}
+
+/// Whether the member was already announced in the guild this year.
+///
+/// Setting a birthday uses this as its announced flag, so moving it to today doesn't earn another
+/// announcement, not even after deleting it first.
+pub fn birthday_announced_this_year(
+  conn: &mut SqliteConnection,
+  guild_id: i64,
+  user_id: i64,
+  now: NaiveDateTime,
+) -> Result<bool, diesel::result::Error> {
+  Ok(count_user_birthday_announcements_since(conn, guild_id, user_id, year_start(now))? > 0)
+}
+
+fn year_start(now: NaiveDateTime) -> NaiveDateTime {
+  NaiveDate::from_ymd_opt(now.year(), 1, 1)
+      .and_then(|date| date.and_hms_opt(0, 0, 0))
+      .expect("January 1st is a valid date")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{birthday_announced_this_year, classify_milestone};
+  use crate::db::queries::{delete_birthday, get_birthday, insert_birthday, record_birthday_announcements};
+  use chrono::NaiveDate;
+  use diesel::connection::SimpleConnection;
+  use diesel::{Connection, SqliteConnection};
+
+  /// An in-memory database with every migration applied.
+  fn migrated_connection() -> SqliteConnection {
+    let mut conn = SqliteConnection::establish(":memory:").unwrap();
+
+    let mut migrations = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    migrations.sort();
+
+    for migration in migrations {
+      conn.batch_execute(&std::fs::read_to_string(migration.join("up.sql")).unwrap()).unwrap();
+    }
+
+    conn
+  }
+
+  #[test]
+  fn classify_milestone_picks_out_special_ages() {
+    assert!(classify_milestone(18).is_some());
+    assert!(classify_milestone(21).is_some());
+    assert!(classify_milestone(30).is_some());
+    assert!(classify_milestone(100).is_some());
+    assert_eq!(classify_milestone(100), Some("💯 A whole century!"));
+  }
+
+  #[test]
+  fn classify_milestone_skips_ordinary_ages() {
+    assert_eq!(classify_milestone(20), None);
+    assert_eq!(classify_milestone(29), None);
+    assert_eq!(classify_milestone(10), None);
+  }
+
+  #[test]
+  fn deleting_and_setting_again_is_not_announced_twice() {
+    let conn = &mut migrated_connection();
+    let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+    let now = today.and_hms_opt(9, 0, 0).unwrap();
+    let birthday_date = NaiveDate::from_ymd_opt(1995, 6, 15).unwrap();
+
+    insert_birthday(conn, 1, 10, birthday_date, false).unwrap();
+    record_birthday_announcements(conn, 10, &[1], now).unwrap();
+
+    let birthday = get_birthday(conn, 1, 10).unwrap().unwrap();
+    delete_birthday(conn, &birthday).unwrap();
+
+    let announced = birthday_announced_this_year(conn, 10, 1, now).unwrap();
+    assert!(announced);
+
+    insert_birthday(conn, 1, 10, birthday_date, announced).unwrap();
+    assert!(get_birthday(conn, 1, 10).unwrap().unwrap().announced_this_year);
+  }
+
+  #[test]
+  fn birthday_announced_this_year_is_per_guild_and_year() {
+    let conn = &mut migrated_connection();
+    let now = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap().and_hms_opt(9, 0, 0).unwrap();
+    let last_year = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap().and_hms_opt(23, 0, 0).unwrap();
+
+    record_birthday_announcements(conn, 10, &[1], now).unwrap();
+    record_birthday_announcements(conn, 10, &[2], last_year).unwrap();
+
+    assert!(!birthday_announced_this_year(conn, 11, 1, now).unwrap());
+    assert!(!birthday_announced_this_year(conn, 10, 2, now).unwrap());
+    assert!(!birthday_announced_this_year(conn, 10, 3, now).unwrap());
+  }
+}
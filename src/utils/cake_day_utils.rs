@@ -0,0 +1,106 @@
+use crate::db::models::GuildSettings;
+use crate::db::queries::{count_audit_entries_since, count_audit_entries_with_value_since, count_birthday_announcements_since, list_pending_cake_day_guilds, mark_cake_days_announced};
+use crate::utils::audit_utils::AuditAction;
+use crate::utils::date_utils::anniversary_years;
+use crate::utils::embed_utils::create_cake_day_embed;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::{ChannelId, CreateMessage, GuildId, Http, Timestamp};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CakeDay {
+  Guild,
+  Bot,
+}
+
+/// What happened in the guild over the past year, from the birthdays and the audit log.
+pub struct YearSummary {
+  pub birthdays_celebrated: i64,
+  /// Every set, edit, restore and pending submission.
+  pub birthday_changes: i64,
+  /// Colors set, picked or restored; resets aren't counted.
+  pub colors_assigned: i64,
+}
+
+pub async fn handle_cake_day_announcements(http: &Arc<Http>, db_pool: Arc<Mutex<SqliteConnection>>) -> Result<(), Box<dyn std::error::Error>> {
+  let mut conn = db_pool.lock().await;
+  let today = Local::now().naive_utc().date();
+
+  let guilds = list_pending_cake_day_guilds(&mut conn, today)?;
+  if guilds.is_empty() {
+    return Ok(());
+  }
+
+  let bot_id = http.get_current_user().await?.id;
+
+  for settings in guilds {
+    let guild_id = GuildId::new(settings.guild_id as u64);
+    let mut cake_days = Vec::new();
+
+    if settings.guild_cake_day && let Some(years) = anniversary_years(timestamp_date(guild_id.created_at()), today) {
+      cake_days.push((CakeDay::Guild, years));
+    }
+
+    if settings.bot_cake_day {
+      match guild_id.member(http, bot_id).await {
+        Ok(member) => {
+          if let Some(years) = member.joined_at.and_then(|joined_at| anniversary_years(timestamp_date(joined_at), today)) {
+            cake_days.push((CakeDay::Bot, years));
+          }
+        }
+        Err(e) => error!("Error fetching own member for guild {}: {:?}", settings.guild_id, e),
+      }
+    }
+
+    for (cake_day, years) in cake_days {
+      if let Err(e) = announce_cake_day(http, &mut conn, &settings, cake_day, years).await {
+        error!("Error announcing cake day for guild {}: {:?}", settings.guild_id, e);
+      }
+    }
+
+    if let Err(e) = mark_cake_days_announced(&mut conn, settings.guild_id, today) {
+      error!("Error marking cake days announced for guild {}: {:?}", settings.guild_id, e);
+    }
+  }
+
+  Ok(())
+}
+
+fn timestamp_date(timestamp: Timestamp) -> NaiveDate {
+  DateTime::from_timestamp(timestamp.unix_timestamp(), 0)
+      .map(|date| date.date_naive())
+      .unwrap_or_default()
+}
+
+/// Announcements come from their own record; birthday and color changes from the audit log.
+pub fn get_year_summary(conn: &mut SqliteConnection, guild_id: i64) -> Result<YearSummary, diesel::result::Error> {
+  let since = Local::now().naive_utc() - Duration::days(365);
+
+  Ok(YearSummary {
+    birthdays_celebrated: count_birthday_announcements_since(conn, guild_id, since)?,
+    birthday_changes: count_audit_entries_since(conn, guild_id, AuditAction::BirthdaySet.as_str(), since)?,
+    colors_assigned: count_audit_entries_with_value_since(conn, guild_id, AuditAction::ColorChange.as_str(), since)?,
+  })
+}
+
+async fn announce_cake_day(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  settings: &GuildSettings,
+  cake_day: CakeDay,
+  years: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let Some(channel_id) = settings.announcements_channel_id else {
+    return Ok(());
+  };
+
+  let summary = get_year_summary(conn, settings.guild_id)?;
+  let embed = create_cake_day_embed(cake_day, years, &summary);
+
+  ChannelId::new(channel_id as u64).send_message(http, CreateMessage::new().embed(embed)).await?;
+
+  Ok(())
+}
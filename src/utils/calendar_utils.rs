@@ -0,0 +1,149 @@
+use crate::db::models::GuildSettings;
+use crate::utils::date_utils::days_in_month;
+use crate::utils::image_utils::{draw_text, encode_png, fill_rect, text_width, truncate_text, FontFamily};
+use chrono::{Datelike, NaiveDate};
+use image::{ImageError, Rgba, RgbaImage};
+use poise::ChoiceParameter;
+use std::collections::BTreeMap;
+
+const MARGIN: u32 = 20;
+const CELL_WIDTH: u32 = 140;
+const CELL_HEIGHT: u32 = 110;
+const HEADER_HEIGHT: u32 = 70;
+const WEEKDAY_HEIGHT: u32 = 30;
+const CELL_GAP: u32 = 4;
+const NAMES_PER_CELL: usize = 3;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Color themes for rendered birthday calendars.
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum CalendarTheme {
+  Dark,
+  Light,
+  Pastel,
+}
+
+struct ThemeColors {
+  background: Rgba<u8>,
+  cell: Rgba<u8>,
+  birthday_cell: Rgba<u8>,
+  title: Rgba<u8>,
+  text: Rgba<u8>,
+  muted: Rgba<u8>,
+  accent: Rgba<u8>,
+}
+
+impl CalendarTheme {
+  fn colors(&self) -> ThemeColors {
+    match self {
+      CalendarTheme::Dark => ThemeColors {
+        background: Rgba([49, 51, 56, 255]),
+        cell: Rgba([43, 45, 49, 255]),
+        birthday_cell: Rgba([64, 56, 36, 255]),
+        title: Rgba([255, 255, 255, 255]),
+        text: Rgba([219, 222, 225, 255]),
+        muted: Rgba([148, 155, 164, 255]),
+        accent: Rgba([241, 196, 15, 255]),
+      },
+      CalendarTheme::Light => ThemeColors {
+        background: Rgba([255, 255, 255, 255]),
+        cell: Rgba([242, 243, 245, 255]),
+        birthday_cell: Rgba([255, 243, 205, 255]),
+        title: Rgba([6, 6, 7, 255]),
+        text: Rgba([49, 51, 56, 255]),
+        muted: Rgba([92, 94, 102, 255]),
+        accent: Rgba([194, 124, 14, 255]),
+      },
+      CalendarTheme::Pastel => ThemeColors {
+        background: Rgba([253, 242, 248, 255]),
+        cell: Rgba([255, 255, 255, 255]),
+        birthday_cell: Rgba([221, 235, 255, 255]),
+        title: Rgba([112, 66, 135, 255]),
+        text: Rgba([74, 74, 104, 255]),
+        muted: Rgba([150, 140, 170, 255]),
+        accent: Rgba([236, 112, 162, 255]),
+      },
+    }
+  }
+}
+
+/// Reads the guild's calendar theme and font, falling back to the defaults.
+pub fn get_calendar_style(settings: Option<&GuildSettings>) -> (CalendarTheme, FontFamily) {
+  let theme = settings
+      .and_then(|settings| CalendarTheme::from_name(&settings.calendar_theme))
+      .unwrap_or(CalendarTheme::Dark);
+  let font = settings
+      .and_then(|settings| FontFamily::from_name(&settings.calendar_font))
+      .unwrap_or(FontFamily::Sans);
+
+  (theme, font)
+}
+
+/// Renders a month grid (weeks starting on Monday) with the names listed on their days.
+pub fn render_birthday_calendar(
+  year: i32,
+  month: u32,
+  names_by_day: &BTreeMap<u32, Vec<String>>,
+  theme: CalendarTheme,
+  font_family: FontFamily,
+) -> Result<Vec<u8>, ImageError> {
+  let colors = theme.colors();
+  let font = font_family.font();
+
+  let first_day = NaiveDate::from_ymd_opt(year, month, 1).expect("Invalid calendar month");
+  let offset = first_day.weekday().num_days_from_monday();
+  let days = days_in_month(year, month);
+  let rows = (offset + days).div_ceil(7);
+
+  let width = MARGIN * 2 + CELL_WIDTH * 7;
+  let height = MARGIN * 2 + HEADER_HEIGHT + WEEKDAY_HEIGHT + CELL_HEIGHT * rows;
+  let mut image = RgbaImage::from_pixel(width, height, colors.background);
+
+  let title = first_day.format("%B %Y").to_string();
+  let title_width = text_width(&font, 40.0, &title);
+  draw_text(&mut image, &font, 40.0, (width as f32 - title_width) / 2.0, MARGIN as f32 + 8.0, &title, colors.title);
+
+  for (index, weekday) in WEEKDAYS.iter().enumerate() {
+    let x = MARGIN + CELL_WIDTH * index as u32;
+    let label_width = text_width(&font, 18.0, weekday);
+    draw_text(
+      &mut image,
+      &font,
+      18.0,
+      x as f32 + (CELL_WIDTH as f32 - label_width) / 2.0,
+      (MARGIN + HEADER_HEIGHT) as f32,
+      weekday,
+      colors.muted,
+    );
+  }
+
+  for day in 1..=days {
+    let slot = offset + day - 1;
+    let x = MARGIN + CELL_WIDTH * (slot % 7);
+    let y = MARGIN + HEADER_HEIGHT + WEEKDAY_HEIGHT + CELL_HEIGHT * (slot / 7);
+    let names = names_by_day.get(&day);
+
+    let cell_color = if names.is_some() { colors.birthday_cell } else { colors.cell };
+    fill_rect(&mut image, x + CELL_GAP / 2, y + CELL_GAP / 2, CELL_WIDTH - CELL_GAP, CELL_HEIGHT - CELL_GAP, cell_color);
+
+    let day_color = if names.is_some() { colors.accent } else { colors.text };
+    draw_text(&mut image, &font, 20.0, (x + 10) as f32, (y + 8) as f32, &day.to_string(), day_color);
+
+    let Some(names) = names else {
+      continue;
+    };
+
+    let max_width = (CELL_WIDTH - 20) as f32;
+    for (line, name) in names.iter().take(NAMES_PER_CELL).enumerate() {
+      let name = truncate_text(&font, 15.0, name, max_width);
+      draw_text(&mut image, &font, 15.0, (x + 10) as f32, (y + 36 + 20 * line as u32) as f32, &name, colors.text);
+    }
+
+    if names.len() > NAMES_PER_CELL {
+      let more = format!("+{} more", names.len() - NAMES_PER_CELL);
+      draw_text(&mut image, &font, 13.0, (x + 10) as f32, (y + 36 + 20 * NAMES_PER_CELL as u32) as f32, &more, colors.muted);
+    }
+  }
+
+  encode_png(&image)
+}
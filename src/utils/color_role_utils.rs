@@ -0,0 +1,258 @@
+use crate::db::queries::{count_color_role_holders, delete_color_role, delete_color_role_holder, forget_personal_color_role, get_color_role, get_color_role_by_color, get_color_role_holder, insert_color_role, list_color_role_cleanup_guilds, list_color_roles, list_personal_color_roles, set_color_role_holder};
+use crate::utils::color_utils::{ColorUtils, Rgb};
+use crate::utils::user_utils::{fetch_all_members, move_roles_below_anchor, place_color_role, user_specific_role_owner};
+use diesel::SqliteConnection;
+use log::{error, info};
+use poise::serenity_prelude::{EditRole, GuildId, Http, RoleId, UserId};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex as StdMutex};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Shared color roles are reference counted across several requests to Discord, so every change
+/// within a guild has to wait for the previous one to finish.
+static SHARED_COLOR_ROLE_LOCKS: LazyLock<StdMutex<HashMap<GuildId, Arc<Mutex<()>>>>> = LazyLock::new(Default::default);
+
+async fn lock_shared_color_roles(guild_id: GuildId) -> OwnedMutexGuard<()> {
+  let lock = SHARED_COLOR_ROLE_LOCKS
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner())
+      .entry(guild_id)
+      .or_default()
+      .clone();
+
+  lock.lock_owned().await
+}
+
+/// Moves the member onto the shared role for the color, creating the role if nobody has it yet.
+///
+/// Returns the color the member had before, if they held a shared role.
+pub async fn assign_shared_color_role(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: GuildId,
+  user_id: UserId,
+  color: Rgb,
+  anchor_id: Option<RoleId>,
+) -> Result<Option<Rgb>, Box<dyn std::error::Error + Send + Sync>> {
+  let _guard = lock_shared_color_roles(guild_id).await;
+
+  let hex = color.to_hex();
+  let roles = guild_id.roles(http).await?;
+
+  let (role_id, created) = match get_color_role_by_color(conn, i64::from(guild_id), &hex)? {
+    Some(color_role) if roles.contains_key(&RoleId::new(color_role.role_id as u64)) => (RoleId::new(color_role.role_id as u64), false),
+    stale => {
+      // The role was deleted by hand; forget it and start over.
+      if let Some(stale) = stale {
+        delete_color_role(conn, stale.role_id)?;
+      }
+
+      let role = guild_id
+          .create_role(http, EditRole::new().name(&hex).colour(color).mentionable(false))
+          .await?;
+
+      let placed = match place_color_role(http, guild_id, role.id, anchor_id).await {
+        Ok(()) => insert_color_role(conn, i64::from(guild_id), i64::from(role.id), &hex).map_err(Into::into),
+        Err(e) => Err(e.into()),
+      };
+      if let Err(e) = placed {
+        discard_shared_color_role(http, conn, guild_id, role.id).await;
+        return Err(e);
+      }
+
+      (role.id, true)
+    }
+  };
+
+  if get_color_role_holder(conn, i64::from(guild_id), i64::from(user_id))? == Some(i64::from(role_id)) {
+    return Ok(Some(color));
+  }
+
+  if let Err(e) = http.add_member_role(guild_id, user_id, role_id, None).await {
+    if created {
+      discard_shared_color_role(http, conn, guild_id, role_id).await;
+    }
+    return Err(e.into());
+  }
+
+  let previous_color = release_held_color_role(http, conn, guild_id, user_id).await?;
+  set_color_role_holder(conn, i64::from(guild_id), i64::from(user_id), i64::from(role_id))?;
+
+  Ok(previous_color)
+}
+
+/// Deletes a shared role that was just created but couldn't be handed out, so it isn't leaked.
+async fn discard_shared_color_role(http: &Http, conn: &mut SqliteConnection, guild_id: GuildId, role_id: RoleId) {
+  if let Err(e) = guild_id.delete_role(http, role_id).await {
+    error!("Error deleting unassigned color role {} in guild {}: {:?}", role_id, guild_id, e);
+  }
+
+  if let Err(e) = delete_color_role(conn, i64::from(role_id)) {
+    error!("Error forgetting unassigned color role {} in guild {}: {:?}", role_id, guild_id, e);
+  }
+}
+
+/// Takes the member's shared role away and deletes it once nobody else holds it.
+///
+/// Returns the color of the released role.
+pub async fn release_shared_color_role(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: GuildId,
+  user_id: UserId,
+) -> Result<Option<Rgb>, Box<dyn std::error::Error + Send + Sync>> {
+  let _guard = lock_shared_color_roles(guild_id).await;
+
+  release_held_color_role(http, conn, guild_id, user_id).await
+}
+
+/// [`release_shared_color_role`] for callers that already hold the guild's lock.
+async fn release_held_color_role(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: GuildId,
+  user_id: UserId,
+) -> Result<Option<Rgb>, Box<dyn std::error::Error + Send + Sync>> {
+  let Some(role_id) = get_color_role_holder(conn, i64::from(guild_id), i64::from(user_id))? else {
+    return Ok(None);
+  };
+
+  let previous_color = get_color_role(conn, role_id)?.and_then(|color_role| ColorUtils::hex_to_rgb(&color_role.color));
+  delete_color_role_holder(conn, i64::from(guild_id), i64::from(user_id))?;
+
+  // The member may have left or had the role removed by hand already.
+  let _ = http.remove_member_role(guild_id, user_id, RoleId::new(role_id as u64), None).await;
+
+  if count_color_role_holders(conn, role_id)? == 0 {
+    if let Err(e) = guild_id.delete_role(http, RoleId::new(role_id as u64)).await {
+      error!("Error deleting unused color role {} in guild {}: {:?}", role_id, guild_id, e);
+    }
+    delete_color_role(conn, role_id)?;
+  }
+
+  Ok(previous_color)
+}
+
+/// Moves every per-user and shared color role right below the anchor role in one batch.
+pub async fn reorder_color_roles(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: GuildId,
+  anchor_id: RoleId,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+  let shared_role_ids: Vec<RoleId> = list_color_roles(conn, i64::from(guild_id))?
+      .into_iter()
+      .map(|color_role| RoleId::new(color_role.role_id as u64))
+      .collect();
+
+  let personal_role_ids: Vec<RoleId> = list_personal_color_roles(conn, i64::from(guild_id))?
+      .into_iter()
+      .map(|personal_role| RoleId::new(personal_role.role_id as u64))
+      .collect();
+
+  let role_ids: Vec<RoleId> = guild_id
+      .roles(http)
+      .await?
+      .into_values()
+      .filter(|role| personal_role_ids.contains(&role.id) || shared_role_ids.contains(&role.id))
+      .map(|role| role.id)
+      .collect();
+
+  move_roles_below_anchor(http, guild_id, anchor_id, &role_ids).await?;
+
+  Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrphanReason {
+  UserLeft,
+  NoHolder,
+}
+
+/// A per-user color role (named after the user id) that nobody needs anymore.
+pub struct OrphanedRole {
+  pub role_id: RoleId,
+  pub user_id: UserId,
+  pub reason: OrphanReason,
+}
+
+/// Finds per-user color roles the bot created whose user left the guild or that nobody holds.
+///
+/// Tracked roles that were deleted by hand are forgotten on the way.
+pub async fn find_orphaned_color_roles(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: GuildId,
+) -> Result<Vec<OrphanedRole>, Box<dyn std::error::Error + Send + Sync>> {
+  let personal_roles = list_personal_color_roles(conn, i64::from(guild_id))?;
+  let roles = guild_id.roles(http).await?;
+  let members = fetch_all_members(http, guild_id).await?;
+
+  let mut orphans = Vec::new();
+  for personal_role in personal_roles {
+    let role_id = RoleId::new(personal_role.role_id as u64);
+    let user_id = UserId::new(personal_role.user_id as u64);
+
+    let Some(role) = roles.get(&role_id) else {
+      forget_personal_color_role(conn, personal_role.role_id)?;
+      continue;
+    };
+
+    // A role renamed by hand isn't a color role anymore.
+    if user_specific_role_owner(role) != Some(user_id) {
+      continue;
+    }
+
+    let reason = if !members.iter().any(|member| member.user.id == user_id) {
+      OrphanReason::UserLeft
+    } else if !members.iter().any(|member| member.roles.contains(&role_id)) {
+      OrphanReason::NoHolder
+    } else {
+      continue;
+    };
+
+    orphans.push(OrphanedRole { role_id, user_id, reason });
+  }
+
+  Ok(orphans)
+}
+
+/// Deletes the roles and returns how many were deleted.
+pub async fn delete_orphaned_color_roles(http: &Http, conn: &mut SqliteConnection, guild_id: GuildId, orphans: &[OrphanedRole]) -> usize {
+  let mut deleted = 0;
+
+  for orphan in orphans {
+    match guild_id.delete_role(http, orphan.role_id).await {
+      Ok(_) => deleted += 1,
+      Err(e) => {
+        error!("Error deleting orphaned color role {} in guild {}: {:?}", orphan.role_id, guild_id, e);
+        continue;
+      }
+    }
+
+    if let Err(e) = forget_personal_color_role(conn, i64::from(orphan.role_id)) {
+      error!("Error forgetting orphaned color role {} in guild {}: {:?}", orphan.role_id, guild_id, e);
+    }
+  }
+
+  deleted
+}
+
+pub async fn handle_color_role_cleanup(http: &Arc<Http>, db_pool: Arc<Mutex<SqliteConnection>>) -> Result<(), Box<dyn std::error::Error>> {
+  let mut conn = db_pool.lock().await;
+
+  for guild_id in list_color_role_cleanup_guilds(&mut conn)? {
+    let guild_id = GuildId::new(guild_id as u64);
+
+    match find_orphaned_color_roles(http, &mut conn, guild_id).await {
+      Ok(orphans) if !orphans.is_empty() => {
+        let deleted = delete_orphaned_color_roles(http, &mut conn, guild_id, &orphans).await;
+        info!("Deleted {} orphaned color roles in guild {}", deleted, guild_id);
+      }
+      Ok(_) => {}
+      Err(e) => error!("Error finding orphaned color roles in guild {}: {:?}", guild_id, e),
+    }
+  }
+
+  Ok(())
+}
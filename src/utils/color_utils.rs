@@ -1,3 +1,6 @@
+use crate::db::models::{GuildSettings, PaletteColor};
+use poise::serenity_prelude::Color;
+use poise::ChoiceParameter;
 use regex::Regex;
 use std::fmt;
 
@@ -20,37 +23,658 @@ impl fmt::Display for HexColorError {
   }
 }
 
+#[derive(Debug)]
+pub enum ColorParseError {
+  Hex(HexColorError),
+  InvalidRgb,
+  InvalidHsl,
+  UnknownName,
+  NotInPalette,
+}
+
+impl fmt::Display for ColorParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ColorParseError::Hex(e) => write!(f, "{}", e),
+      ColorParseError::InvalidRgb => write!(f, "Invalid rgb() color, expected e.g. rgb(255, 87, 51)."),
+      ColorParseError::InvalidHsl => write!(f, "Invalid hsl() color, expected e.g. hsl(11, 100%, 60%)."),
+      ColorParseError::UnknownName => write!(f, "Unknown color name."),
+      ColorParseError::NotInPalette => write!(f, "Only colors from this server's palette can be used."),
+    }
+  }
+}
+
+impl From<HexColorError> for ColorParseError {
+  fn from(e: HexColorError) -> Self {
+    ColorParseError::Hex(e)
+  }
+}
+
+/// A color every accepted input format is normalized to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+}
+
+impl Rgb {
+  pub const fn new(r: u8, g: u8, b: u8) -> Self {
+    Rgb { r, g, b }
+  }
+
+  pub fn to_hex(self) -> String {
+    format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+  }
+}
+
+impl fmt::Display for Rgb {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.to_hex())
+  }
+}
+
+impl From<Rgb> for Color {
+  fn from(rgb: Rgb) -> Self {
+    Color::from_rgb(rgb.r, rgb.g, rgb.b)
+  }
+}
+
+impl From<Color> for Rgb {
+  fn from(color: Color) -> Self {
+    Rgb::new(color.r(), color.g(), color.b())
+  }
+}
+
+/// Discord's chat background in the dark and light themes.
+pub const DISCORD_DARK_BACKGROUND: Rgb = Rgb::new(0x31, 0x33, 0x38);
+pub const DISCORD_LIGHT_BACKGROUND: Rgb = Rgb::new(0xff, 0xff, 0xff);
+pub const DEFAULT_CONTRAST_THRESHOLD: f64 = 2.0;
+/// The best contrast any color can reach against both backgrounds at once.
+pub const MAX_CONTRAST_THRESHOLD: f64 = 3.5;
+
+/// What `/color set` does with colors that are hard to read on either theme.
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum ContrastPolicy {
+  Off,
+  Warn,
+  Adjust,
+  Reject,
+}
+
+/// Reads the guild's contrast policy and threshold, falling back to the defaults.
+pub fn get_contrast_policy(settings: Option<&GuildSettings>) -> (ContrastPolicy, f64) {
+  let policy = settings
+      .and_then(|settings| ContrastPolicy::from_name(&settings.color_contrast_policy))
+      .unwrap_or(ContrastPolicy::Warn);
+  let threshold = settings
+      .map(|settings| settings.color_contrast_threshold)
+      .unwrap_or(DEFAULT_CONTRAST_THRESHOLD);
+
+  (policy, threshold)
+}
+
+/// WCAG contrast ratios of a color against both Discord themes.
+#[derive(Debug, Clone, Copy)]
+pub struct ContrastReport {
+  pub dark: f64,
+  pub light: f64,
+}
+
+impl ContrastReport {
+  pub fn of(color: Rgb) -> Self {
+    ContrastReport {
+      dark: ColorUtils::contrast_ratio(color, DISCORD_DARK_BACKGROUND),
+      light: ColorUtils::contrast_ratio(color, DISCORD_LIGHT_BACKGROUND),
+    }
+  }
+
+  pub fn min(&self) -> f64 {
+    self.dark.min(self.light)
+  }
+
+  pub fn is_readable(&self, threshold: f64) -> bool {
+    self.min() >= threshold
+  }
+}
+
 pub struct ColorUtils;
 
 impl ColorUtils {
+  /// Parses `#f53`, `#ff5733`, `rgb(255, 87, 51)`, `hsl(11, 100%, 60%)` or a CSS color name.
+  pub fn parse_color(input: &str) -> Result<Rgb, ColorParseError> {
+    let input = input.trim().to_lowercase();
+
+    if let Some(args) = Self::function_args(&input, &["rgb", "rgba"]) {
+      return Self::parse_rgb(&args).ok_or(ColorParseError::InvalidRgb);
+    }
+
+    if let Some(args) = Self::function_args(&input, &["hsl", "hsla"]) {
+      return Self::parse_hsl(&args).ok_or(ColorParseError::InvalidHsl);
+    }
+
+    if let Some(rgb) = Self::named_color(&input) {
+      return Ok(rgb);
+    }
+
+    if input.starts_with('#') || input.chars().all(|c| c.is_ascii_hexdigit()) {
+      Self::validate_hex_color(&input)?;
+      return Self::hex_to_rgb(&input).ok_or(ColorParseError::Hex(HexColorError::InvalidHex));
+    }
+
+    Err(ColorParseError::UnknownName)
+  }
+
+  /// Looks the input up in the guild palette by name, then parses it; palette-only guilds reject anything else.
+  pub fn resolve_color(input: &str, palette: &[PaletteColor], palette_only: bool) -> Result<Rgb, ColorParseError> {
+    let palette_colors = palette
+        .iter()
+        .filter_map(|entry| Some((entry, Self::hex_to_rgb(&entry.color)?)))
+        .collect::<Vec<(&PaletteColor, Rgb)>>();
+
+    if let Some((_, rgb)) = palette_colors.iter().find(|(entry, _)| entry.name.eq_ignore_ascii_case(input.trim())) {
+      return Ok(*rgb);
+    }
+
+    let parsed = Self::parse_color(input)?;
+    if palette_only && !palette_colors.iter().any(|(_, rgb)| *rgb == parsed) {
+      return Err(ColorParseError::NotInPalette);
+    }
+
+    Ok(parsed)
+  }
+
   pub fn validate_hex_color(hex: &str) -> Result<(), HexColorError> {
     let hex_regex = Regex::new(r"^#?([A-Fa-f0-9]{6}|[A-Fa-f0-9]{3})$").unwrap();
 
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if digits.len() != 6 && digits.len() != 3 {
+      return Err(HexColorError::InvalidLength);
+    }
+
     if !hex_regex.is_match(hex) {
       return Err(HexColorError::InvalidHex);
     }
 
-    if hex.len() != 7 && hex.len() != 6 {
-      return Err(HexColorError::InvalidLength);
+    Ok(())
+  }
+
+  pub fn hex_to_rgb(hex: &str) -> Option<Rgb> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+    match hex.len() {
+      6 => {
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+        Some(Rgb::new(r, g, b))
+      }
+      // Short hex doubles every digit, so #f53 is #ff5533.
+      3 => {
+        let mut channels = hex.chars().map(|c| c.to_digit(16).map(|digit| (digit * 17) as u8));
+        Some(Rgb::new(channels.next()??, channels.next()??, channels.next()??))
+      }
+      _ => None,
     }
+  }
 
-    Ok(())
+  pub fn named_color(name: &str) -> Option<Rgb> {
+    CSS_COLORS
+        .iter()
+        .find(|(css_name, _)| *css_name == name)
+        .map(|(_, hex)| Rgb::new((hex >> 16) as u8, (hex >> 8) as u8, *hex as u8))
   }
 
-  pub fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
-    let hex = if hex.starts_with('#') {
-      &hex[1..]
-    } else {
-      hex
+  /// Splits `name(a, b, c)` or `name(a b c / alpha)` into its arguments, dropping the alpha.
+  fn function_args(input: &str, names: &[&str]) -> Option<Vec<String>> {
+    let (name, rest) = input.split_once('(')?;
+    if !names.contains(&name.trim()) {
+      return None;
+    }
+
+    let args = rest.strip_suffix(')')?.split('/').next()?;
+
+    Some(args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .take(3)
+        .map(str::to_string)
+        .collect())
+  }
+
+  fn parse_rgb(args: &[String]) -> Option<Rgb> {
+    let [r, g, b] = args else {
+      return None;
     };
 
-    if hex.len() == 6 {
-      let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-      let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-      let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-      Some((r, g, b))
-    } else {
-      None
+    let channel = |arg: &str| -> Option<u8> {
+      let value = match arg.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+        None => arg.parse::<f64>().ok()?,
+      };
+
+      (0.0..=255.0).contains(&value).then(|| value.round() as u8)
+    };
+
+    Some(Rgb::new(channel(r)?, channel(g)?, channel(b)?))
+  }
+
+  fn parse_hsl(args: &[String]) -> Option<Rgb> {
+    let [h, s, l] = args else {
+      return None;
+    };
+
+    let hue = h.strip_suffix("deg").unwrap_or(h).parse::<f64>().ok()?.rem_euclid(360.0);
+    let percent = |arg: &str| -> Option<f64> {
+      let value = arg.strip_suffix('%').unwrap_or(arg).parse::<f64>().ok()?;
+      (0.0..=100.0).contains(&value).then_some(value / 100.0)
+    };
+
+    Some(Self::hsl_to_rgb(hue, percent(s)?, percent(l)?))
+  }
+
+  /// WCAG relative luminance, from 0 (black) to 1 (white).
+  pub fn relative_luminance(color: Rgb) -> f64 {
+    let channel = |value: u8| {
+      let value = value as f64 / 255.0;
+      if value <= 0.03928 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+    };
+
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+  }
+
+  /// WCAG contrast ratio, from 1 (identical) to 21 (black on white).
+  pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (a, b) = (Self::relative_luminance(a), Self::relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+  }
+
+  /// Keeps the hue and saturation and moves the lightness as little as possible to reach the threshold on both themes.
+  pub fn adjust_for_contrast(color: Rgb, threshold: f64) -> Rgb {
+    let (hue, saturation, lightness) = Self::rgb_to_hsl(color);
+
+    let candidates = (0..=100)
+        .map(|step| step as f64 / 100.0)
+        .map(|candidate| (candidate, Self::hsl_to_rgb(hue, saturation, candidate)))
+        .collect::<Vec<(f64, Rgb)>>();
+
+    let readable = candidates
+        .iter()
+        .filter(|(_, candidate)| ContrastReport::of(*candidate).is_readable(threshold))
+        .min_by(|(a, _), (b, _)| (a - lightness).abs().total_cmp(&(b - lightness).abs()));
+
+    match readable {
+      Some((_, adjusted)) => *adjusted,
+      None => candidates
+          .iter()
+          .max_by(|(_, a), (_, b)| ContrastReport::of(*a).min().total_cmp(&ContrastReport::of(*b).min()))
+          .map(|(_, best)| *best)
+          .unwrap_or(color),
     }
   }
+
+  /// Median cut: keeps splitting the box with the widest channel range at its median,
+  /// then returns the average of each box, most common first.
+  pub fn dominant_colors(pixels: &[Rgb], count: usize) -> Vec<Rgb> {
+    if pixels.is_empty() || count == 0 {
+      return Vec::new();
+    }
+
+    let channels = |color: &Rgb| [color.r, color.g, color.b];
+    let widest_channel = |colors: &[Rgb]| {
+      (0..3)
+          .map(|channel| {
+            let values = colors.iter().map(|color| channels(color)[channel]);
+            let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+            (channel, range)
+          })
+          .max_by_key(|(_, range)| *range)
+          .unwrap_or((0, 0))
+    };
+
+    let mut boxes = vec![pixels.to_vec()];
+
+    while boxes.len() < count {
+      let widest = boxes
+          .iter()
+          .enumerate()
+          .map(|(index, colors)| (index, widest_channel(colors)))
+          .filter(|(_, (_, range))| *range > 0)
+          .max_by_key(|(_, (_, range))| *range);
+
+      // Every box is a single color already.
+      let Some((index, (channel, _))) = widest else {
+        break;
+      };
+
+      let mut lower = boxes.swap_remove(index);
+      lower.sort_by_key(|color| channels(color)[channel]);
+
+      // Split at the median, but never between equal values, so a flat area stays in one box.
+      let median = channels(&lower[lower.len() / 2])[channel];
+      let split = match lower.iter().position(|color| channels(color)[channel] >= median) {
+        Some(0) | None => lower.iter().position(|color| channels(color)[channel] > median).unwrap_or(lower.len() / 2),
+        Some(split) => split,
+      };
+      let upper = lower.split_off(split);
+      boxes.push(lower);
+      boxes.push(upper);
+    }
+
+    boxes.sort_by_key(|colors| std::cmp::Reverse(colors.len()));
+    boxes
+        .iter()
+        .map(|colors| {
+          let sum = colors.iter().fold([0u64; 3], |sum, color| {
+            let [r, g, b] = channels(color);
+            [sum[0] + r as u64, sum[1] + g as u64, sum[2] + b as u64]
+          });
+          let len = colors.len() as u64;
+          Rgb::new((sum[0] / len) as u8, (sum[1] / len) as u8, (sum[2] / len) as u8)
+        })
+        .collect()
+  }
+
+  pub fn rgb_to_hsl(color: Rgb) -> (f64, f64, f64) {
+    let (r, g, b) = (color.r as f64 / 255.0, color.g as f64 / 255.0, color.b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+      return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+      60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+      60.0 * ((b - r) / delta + 2.0)
+    } else {
+      60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (hue, saturation, lightness)
+  }
+
+  pub fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match hue as u32 / 60 {
+      0 => (chroma, x, 0.0),
+      1 => (x, chroma, 0.0),
+      2 => (0.0, chroma, x),
+      3 => (0.0, x, chroma),
+      4 => (x, 0.0, chroma),
+      _ => (chroma, 0.0, x),
+    };
+
+    let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb::new(channel(r), channel(g), channel(b))
+  }
+}
+
+/// The CSS named colors.
+const CSS_COLORS: [(&str, u32); 148] = [
+  ("aliceblue", 0xf0f8ff),
+  ("antiquewhite", 0xfaebd7),
+  ("aqua", 0x00ffff),
+  ("aquamarine", 0x7fffd4),
+  ("azure", 0xf0ffff),
+  ("beige", 0xf5f5dc),
+  ("bisque", 0xffe4c4),
+  ("black", 0x000000),
+  ("blanchedalmond", 0xffebcd),
+  ("blue", 0x0000ff),
+  ("blueviolet", 0x8a2be2),
+  ("brown", 0xa52a2a),
+  ("burlywood", 0xdeb887),
+  ("cadetblue", 0x5f9ea0),
+  ("chartreuse", 0x7fff00),
+  ("chocolate", 0xd2691e),
+  ("coral", 0xff7f50),
+  ("cornflowerblue", 0x6495ed),
+  ("cornsilk", 0xfff8dc),
+  ("crimson", 0xdc143c),
+  ("cyan", 0x00ffff),
+  ("darkblue", 0x00008b),
+  ("darkcyan", 0x008b8b),
+  ("darkgoldenrod", 0xb8860b),
+  ("darkgray", 0xa9a9a9),
+  ("darkgreen", 0x006400),
+  ("darkgrey", 0xa9a9a9),
+  ("darkkhaki", 0xbdb76b),
+  ("darkmagenta", 0x8b008b),
+  ("darkolivegreen", 0x556b2f),
+  ("darkorange", 0xff8c00),
+  ("darkorchid", 0x9932cc),
+  ("darkred", 0x8b0000),
+  ("darksalmon", 0xe9967a),
+  ("darkseagreen", 0x8fbc8f),
+  ("darkslateblue", 0x483d8b),
+  ("darkslategray", 0x2f4f4f),
+  ("darkslategrey", 0x2f4f4f),
+  ("darkturquoise", 0x00ced1),
+  ("darkviolet", 0x9400d3),
+  ("deeppink", 0xff1493),
+  ("deepskyblue", 0x00bfff),
+  ("dimgray", 0x696969),
+  ("dimgrey", 0x696969),
+  ("dodgerblue", 0x1e90ff),
+  ("firebrick", 0xb22222),
+  ("floralwhite", 0xfffaf0),
+  ("forestgreen", 0x228b22),
+  ("fuchsia", 0xff00ff),
+  ("gainsboro", 0xdcdcdc),
+  ("ghostwhite", 0xf8f8ff),
+  ("gold", 0xffd700),
+  ("goldenrod", 0xdaa520),
+  ("gray", 0x808080),
+  ("green", 0x008000),
+  ("greenyellow", 0xadff2f),
+  ("grey", 0x808080),
+  ("honeydew", 0xf0fff0),
+  ("hotpink", 0xff69b4),
+  ("indianred", 0xcd5c5c),
+  ("indigo", 0x4b0082),
+  ("ivory", 0xfffff0),
+  ("khaki", 0xf0e68c),
+  ("lavender", 0xe6e6fa),
+  ("lavenderblush", 0xfff0f5),
+  ("lawngreen", 0x7cfc00),
+  ("lemonchiffon", 0xfffacd),
+  ("lightblue", 0xadd8e6),
+  ("lightcoral", 0xf08080),
+  ("lightcyan", 0xe0ffff),
+  ("lightgoldenrodyellow", 0xfafad2),
+  ("lightgray", 0xd3d3d3),
+  ("lightgreen", 0x90ee90),
+  ("lightgrey", 0xd3d3d3),
+  ("lightpink", 0xffb6c1),
+  ("lightsalmon", 0xffa07a),
+  ("lightseagreen", 0x20b2aa),
+  ("lightskyblue", 0x87cefa),
+  ("lightslategray", 0x778899),
+  ("lightslategrey", 0x778899),
+  ("lightsteelblue", 0xb0c4de),
+  ("lightyellow", 0xffffe0),
+  ("lime", 0x00ff00),
+  ("limegreen", 0x32cd32),
+  ("linen", 0xfaf0e6),
+  ("magenta", 0xff00ff),
+  ("maroon", 0x800000),
+  ("mediumaquamarine", 0x66cdaa),
+  ("mediumblue", 0x0000cd),
+  ("mediumorchid", 0xba55d3),
+  ("mediumpurple", 0x9370db),
+  ("mediumseagreen", 0x3cb371),
+  ("mediumslateblue", 0x7b68ee),
+  ("mediumspringgreen", 0x00fa9a),
+  ("mediumturquoise", 0x48d1cc),
+  ("mediumvioletred", 0xc71585),
+  ("midnightblue", 0x191970),
+  ("mintcream", 0xf5fffa),
+  ("mistyrose", 0xffe4e1),
+  ("moccasin", 0xffe4b5),
+  ("navajowhite", 0xffdead),
+  ("navy", 0x000080),
+  ("oldlace", 0xfdf5e6),
+  ("olive", 0x808000),
+  ("olivedrab", 0x6b8e23),
+  ("orange", 0xffa500),
+  ("orangered", 0xff4500),
+  ("orchid", 0xda70d6),
+  ("palegoldenrod", 0xeee8aa),
+  ("palegreen", 0x98fb98),
+  ("paleturquoise", 0xafeeee),
+  ("palevioletred", 0xdb7093),
+  ("papayawhip", 0xffefd5),
+  ("peachpuff", 0xffdab9),
+  ("peru", 0xcd853f),
+  ("pink", 0xffc0cb),
+  ("plum", 0xdda0dd),
+  ("powderblue", 0xb0e0e6),
+  ("purple", 0x800080),
+  ("rebeccapurple", 0x663399),
+  ("red", 0xff0000),
+  ("rosybrown", 0xbc8f8f),
+  ("royalblue", 0x4169e1),
+  ("saddlebrown", 0x8b4513),
+  ("salmon", 0xfa8072),
+  ("sandybrown", 0xf4a460),
+  ("seagreen", 0x2e8b57),
+  ("seashell", 0xfff5ee),
+  ("sienna", 0xa0522d),
+  ("silver", 0xc0c0c0),
+  ("skyblue", 0x87ceeb),
+  ("slateblue", 0x6a5acd),
+  ("slategray", 0x708090),
+  ("slategrey", 0x708090),
+  ("snow", 0xfffafa),
+  ("springgreen", 0x00ff7f),
+  ("steelblue", 0x4682b4),
+  ("tan", 0xd2b48c),
+  ("teal", 0x008080),
+  ("thistle", 0xd8bfd8),
+  ("tomato", 0xff6347),
+  ("turquoise", 0x40e0d0),
+  ("violet", 0xee82ee),
+  ("wheat", 0xf5deb3),
+  ("white", 0xffffff),
+  ("whitesmoke", 0xf5f5f5),
+  ("yellow", 0xffff00),
+  ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_color_accepts_hex() {
+    assert_eq!(ColorUtils::parse_color("#ff5733").unwrap(), Rgb::new(255, 87, 51));
+    assert_eq!(ColorUtils::parse_color("FF5733").unwrap(), Rgb::new(255, 87, 51));
+    assert_eq!(ColorUtils::parse_color("#f53").unwrap(), Rgb::new(255, 85, 51));
+  }
+
+  #[test]
+  fn parse_color_rejects_bad_hex() {
+    assert!(matches!(ColorUtils::parse_color("#ff57"), Err(ColorParseError::Hex(HexColorError::InvalidLength))));
+    assert!(matches!(ColorUtils::parse_color("#gg5733"), Err(ColorParseError::Hex(HexColorError::InvalidHex))));
+  }
+
+  #[test]
+  fn parse_color_accepts_rgb_and_hsl() {
+    assert_eq!(ColorUtils::parse_color("rgb(255, 87, 51)").unwrap(), Rgb::new(255, 87, 51));
+    assert_eq!(ColorUtils::parse_color("rgba(255 87 51 / 0.5)").unwrap(), Rgb::new(255, 87, 51));
+    assert_eq!(ColorUtils::parse_color("rgb(100%, 0%, 0%)").unwrap(), Rgb::new(255, 0, 0));
+    assert_eq!(ColorUtils::parse_color("hsl(120, 100%, 50%)").unwrap(), Rgb::new(0, 255, 0));
+    assert_eq!(ColorUtils::parse_color("hsl(-120deg, 100%, 50%)").unwrap(), Rgb::new(0, 0, 255));
+  }
+
+  #[test]
+  fn parse_color_rejects_out_of_range_functions() {
+    assert!(matches!(ColorUtils::parse_color("rgb(256, 0, 0)"), Err(ColorParseError::InvalidRgb)));
+    assert!(matches!(ColorUtils::parse_color("rgb(1, 2)"), Err(ColorParseError::InvalidRgb)));
+    assert!(matches!(ColorUtils::parse_color("hsl(0, 120%, 50%)"), Err(ColorParseError::InvalidHsl)));
+  }
+
+  #[test]
+  fn parse_color_accepts_names_in_any_case() {
+    assert_eq!(ColorUtils::parse_color("RebeccaPurple").unwrap(), Rgb::new(0x66, 0x33, 0x99));
+    assert!(matches!(ColorUtils::parse_color("notacolor"), Err(ColorParseError::UnknownName)));
+  }
+
+  #[test]
+  fn contrast_ratio_spans_one_to_twenty_one() {
+    let black = Rgb::new(0, 0, 0);
+    let white = Rgb::new(255, 255, 255);
+
+    assert!((ColorUtils::contrast_ratio(black, white) - 21.0).abs() < 1e-9);
+    assert!((ColorUtils::contrast_ratio(white, black) - 21.0).abs() < 1e-9);
+    assert!((ColorUtils::contrast_ratio(white, white) - 1.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn adjust_for_contrast_keeps_readable_colors() {
+    let color = Rgb::new(0x80, 0x80, 0x80);
+    assert!(ContrastReport::of(color).is_readable(2.0));
+    assert_eq!(ColorUtils::adjust_for_contrast(color, 2.0), color);
+  }
+
+  #[test]
+  fn adjust_for_contrast_reaches_the_threshold() {
+    let dark_blue = Rgb::new(0x00, 0x00, 0x40);
+    assert!(!ContrastReport::of(dark_blue).is_readable(DEFAULT_CONTRAST_THRESHOLD));
+
+    let adjusted = ColorUtils::adjust_for_contrast(dark_blue, DEFAULT_CONTRAST_THRESHOLD);
+    assert!(ContrastReport::of(adjusted).is_readable(DEFAULT_CONTRAST_THRESHOLD));
+
+    // The hue survives the adjustment.
+    let (hue, _, _) = ColorUtils::rgb_to_hsl(adjusted);
+    assert!((hue - 240.0).abs() < 1.0);
+  }
+
+  #[test]
+  fn adjust_for_contrast_settles_for_the_best_when_unreachable() {
+    let adjusted = ColorUtils::adjust_for_contrast(Rgb::new(0xff, 0x00, 0x00), 21.0);
+    let best = (0..=100)
+        .map(|step| ColorUtils::hsl_to_rgb(0.0, 1.0, step as f64 / 100.0))
+        .map(|candidate| ContrastReport::of(candidate).min())
+        .fold(0.0, f64::max);
+
+    assert_eq!(ContrastReport::of(adjusted).min(), best);
+  }
+
+  #[test]
+  fn dominant_colors_of_a_single_color() {
+    let pixels = vec![Rgb::new(10, 20, 30); 50];
+    assert_eq!(ColorUtils::dominant_colors(&pixels, 3), vec![Rgb::new(10, 20, 30)]);
+  }
+
+  #[test]
+  fn dominant_colors_most_common_first() {
+    let mut pixels = vec![Rgb::new(0, 0, 255); 10];
+    pixels.extend(vec![Rgb::new(255, 0, 0); 30]);
+
+    assert_eq!(ColorUtils::dominant_colors(&pixels, 2), vec![Rgb::new(255, 0, 0), Rgb::new(0, 0, 255)]);
+  }
+
+  #[test]
+  fn dominant_colors_never_exceed_the_distinct_colors() {
+    let pixels = [Rgb::new(255, 0, 0), Rgb::new(0, 255, 0), Rgb::new(0, 255, 0), Rgb::new(0, 0, 255)];
+    let colors = ColorUtils::dominant_colors(&pixels, 10);
+
+    assert_eq!(colors.len(), 3);
+    assert_eq!(colors[0], Rgb::new(0, 255, 0));
+  }
+
+  #[test]
+  fn dominant_colors_of_nothing() {
+    assert!(ColorUtils::dominant_colors(&[], 3).is_empty());
+    assert!(ColorUtils::dominant_colors(&[Rgb::new(1, 2, 3)], 0).is_empty());
+  }
 }
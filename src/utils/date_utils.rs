@@ -41,17 +41,28 @@ pub fn format_date_without_year(date: NaiveDate) -> String {
 }
 
 pub fn days_until_next_birthday(birthday: NaiveDate) -> i64 {
-  let today = chrono::Utc::now().naive_utc().date();
-  let mut next_birthday = birthday.with_year(today.year()).unwrap();
+  days_until_birthday_from(birthday, chrono::Utc::now().naive_utc().date())
+}
+
+fn days_until_birthday_from(birthday: NaiveDate, today: NaiveDate) -> i64 {
+  let mut next_birthday = birthday_in_year(birthday, today.year());
 
   if next_birthday < today {
-    next_birthday = next_birthday.with_year(today.year() + 1).unwrap();
+    next_birthday = birthday_in_year(birthday, today.year() + 1);
   }
 
   let duration = next_birthday.signed_duration_since(today);
   duration.num_days()
 }
 
+/// The birthday in the given year; leap day birthdays fall on February 28th in common years.
+fn birthday_in_year(birthday: NaiveDate, year: i32) -> NaiveDate {
+  birthday
+      .with_year(year)
+      .or_else(|| NaiveDate::from_ymd_opt(year, 2, 28))
+      .expect("February 28th exists in every year")
+}
+
 pub fn format_announcment_date(date: NaiveDate) -> String {
   date.format("%m-%d").to_string()
 }
@@ -71,3 +82,171 @@ pub fn calculate_age(birthday: NaiveDate) -> i32 {
 
   age
 }
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+  let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+
+  NaiveDate::from_ymd_opt(next_year, next_month, 1)
+      .and_then(|date| date.pred_opt())
+      .map(|date| date.day())
+      .unwrap_or(31)
+}
+
+/// Whole years since `date` if today is its anniversary; Feb 29 dates are celebrated on Feb 28 in common years.
+pub fn anniversary_years(date: NaiveDate, today: NaiveDate) -> Option<i32> {
+  let is_leap_day = date.month() == 2 && date.day() == 29;
+  let falls_today = (today.month() == date.month() && today.day() == date.day())
+      || (is_leap_day && today.month() == 2 && today.day() == 28 && days_in_month(today.year(), 2) == 28);
+
+  let years = today.year() - date.year();
+  (falls_today && years > 0).then_some(years)
+}
+
+pub fn western_zodiac(date: NaiveDate) -> &'static str {
+  // Each sign starts on the given day of its month and runs until the next one starts.
+  const SIGNS: [(u32, u32, &str); 12] = [
+    (1, 20, "♒ Aquarius"),
+    (2, 19, "♓ Pisces"),
+    (3, 21, "♈ Aries"),
+    (4, 20, "♉ Taurus"),
+    (5, 21, "♊ Gemini"),
+    (6, 21, "♋ Cancer"),
+    (7, 23, "♌ Leo"),
+    (8, 23, "♍ Virgo"),
+    (9, 23, "♎ Libra"),
+    (10, 23, "♏ Scorpio"),
+    (11, 22, "♐ Sagittarius"),
+    (12, 22, "♑ Capricorn"),
+  ];
+
+  SIGNS
+      .iter()
+      .rev()
+      .find(|(month, day, _)| (date.month(), date.day()) >= (*month, *day))
+      .map(|(_, _, sign)| *sign)
+      .unwrap_or("♑ Capricorn")
+}
+
+/// Lunar New Year (month, day) for every year from `FIRST_LUNAR_NEW_YEAR` on.
+const LUNAR_NEW_YEARS: [(u32, u32); 81] = [
+  (2, 17), (2, 6), (1, 27), (2, 14), (2, 3), (1, 24), (2, 12), (1, 31), (2, 18), (2, 8), // 1950s
+  (1, 28), (2, 15), (2, 5), (1, 25), (2, 13), (2, 2), (1, 21), (2, 9), (1, 30), (2, 17), // 1960s
+  (2, 6), (1, 27), (2, 15), (2, 3), (1, 23), (2, 11), (1, 31), (2, 18), (2, 7), (1, 28), // 1970s
+  (2, 16), (2, 5), (1, 25), (2, 13), (2, 2), (2, 20), (2, 9), (1, 29), (2, 17), (2, 6), // 1980s
+  (1, 27), (2, 15), (2, 4), (1, 23), (2, 10), (1, 31), (2, 19), (2, 7), (1, 28), (2, 16), // 1990s
+  (2, 5), (1, 24), (2, 12), (2, 1), (1, 22), (2, 9), (1, 29), (2, 18), (2, 7), (1, 26), // 2000s
+  (2, 14), (2, 3), (1, 23), (2, 10), (1, 31), (2, 19), (2, 8), (1, 28), (2, 16), (2, 5), // 2010s
+  (1, 25), (2, 12), (2, 1), (1, 22), (2, 10), (1, 29), (2, 17), (2, 6), (1, 26), (2, 13), // 2020s
+  (2, 3), // 2030
+];
+const FIRST_LUNAR_NEW_YEAR: i32 = 1950;
+
+/// Animal of the Chinese year the date falls in. Dates before that year's Lunar New Year
+/// (somewhere between January 21 and February 20) belong to the previous year's animal.
+///
+/// Returns `None` for dates inside that window in years the table doesn't cover.
+pub fn chinese_zodiac(date: NaiveDate) -> Option<&'static str> {
+  const ANIMALS: [&str; 12] = [
+    "🐀 Rat", "🐂 Ox", "🐅 Tiger", "🐇 Rabbit", "🐉 Dragon", "🐍 Snake",
+    "🐎 Horse", "🐐 Goat", "🐒 Monkey", "🐓 Rooster", "🐕 Dog", "🐖 Pig",
+  ];
+
+  let month_day = (date.month(), date.day());
+  let new_year = usize::try_from(date.year() - FIRST_LUNAR_NEW_YEAR)
+      .ok()
+      .and_then(|index| LUNAR_NEW_YEARS.get(index).copied());
+
+  let year = match new_year {
+    Some(new_year) if month_day < new_year => date.year() - 1,
+    Some(_) => date.year(),
+    None if month_day < (1, 21) => date.year() - 1,
+    None if month_day > (2, 20) => date.year(),
+    None => return None,
+  };
+
+  // 2020 was a year of the Rat.
+  Some(ANIMALS[(year - 2020).rem_euclid(12) as usize])
+}
+
+pub fn birthstone(month: u32) -> &'static str {
+  match month {
+    1 => "Garnet",
+    2 => "Amethyst",
+    3 => "Aquamarine",
+    4 => "Diamond",
+    5 => "Emerald",
+    6 => "Pearl",
+    7 => "Ruby",
+    8 => "Peridot",
+    9 => "Sapphire",
+    10 => "Opal",
+    11 => "Topaz",
+    _ => "Turquoise",
+  }
+}
+
+pub fn format_weekday(date: NaiveDate) -> String {
+  date.format("%A").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+  }
+
+  #[test]
+  fn chinese_zodiac_uses_lunar_new_year() {
+    // Lunar New Year 2000 was on February 5.
+    assert_eq!(chinese_zodiac(date(2000, 2, 4)), Some("🐇 Rabbit"));
+    assert_eq!(chinese_zodiac(date(2000, 2, 5)), Some("🐉 Dragon"));
+    assert_eq!(chinese_zodiac(date(2020, 1, 24)), Some("🐖 Pig"));
+    assert_eq!(chinese_zodiac(date(2020, 1, 25)), Some("🐀 Rat"));
+  }
+
+  #[test]
+  fn chinese_zodiac_outside_the_table() {
+    assert_eq!(chinese_zodiac(date(1940, 1, 10)), Some("🐇 Rabbit"));
+    assert_eq!(chinese_zodiac(date(1940, 3, 1)), Some("🐉 Dragon"));
+    assert_eq!(chinese_zodiac(date(1940, 2, 1)), None);
+  }
+
+  #[test]
+  fn lunar_new_years_stay_in_their_window() {
+    for (index, (month, day)) in LUNAR_NEW_YEARS.iter().enumerate() {
+      let new_year = date(FIRST_LUNAR_NEW_YEAR + index as i32, *month, *day);
+      assert!((*month, *day) >= (1, 21) && (*month, *day) <= (2, 20), "{}", new_year);
+    }
+  }
+
+  #[test]
+  fn western_zodiac_boundaries() {
+    assert_eq!(western_zodiac(date(2000, 1, 19)), "♑ Capricorn");
+    assert_eq!(western_zodiac(date(2000, 1, 20)), "♒ Aquarius");
+    assert_eq!(western_zodiac(date(2000, 12, 22)), "♑ Capricorn");
+  }
+
+  #[test]
+  fn anniversary_years_on_leap_day() {
+    assert_eq!(anniversary_years(date(2020, 2, 29), date(2021, 2, 28)), Some(1));
+    assert_eq!(anniversary_years(date(2020, 2, 29), date(2024, 2, 28)), None);
+    assert_eq!(anniversary_years(date(2020, 2, 29), date(2024, 2, 29)), Some(4));
+    assert_eq!(anniversary_years(date(2024, 3, 1), date(2024, 3, 1)), None);
+  }
+
+  #[test]
+  fn days_until_birthday_on_leap_day() {
+    assert_eq!(days_until_birthday_from(date(2000, 2, 29), date(2026, 2, 1)), 27);
+    assert_eq!(days_until_birthday_from(date(2000, 2, 29), date(2026, 2, 28)), 0);
+    assert_eq!(days_until_birthday_from(date(2000, 2, 29), date(2027, 3, 1)), 365);
+    assert_eq!(days_until_birthday_from(date(2000, 2, 29), date(2028, 2, 28)), 1);
+  }
+
+  #[test]
+  fn days_until_birthday_wraps_to_next_year() {
+    assert_eq!(days_until_birthday_from(date(1990, 6, 15), date(2026, 6, 15)), 0);
+    assert_eq!(days_until_birthday_from(date(1990, 6, 15), date(2026, 6, 16)), 364);
+  }
+}
@@ -1,24 +1,220 @@
+use crate::db::models::{BirthdayWish, CustomEvent};
+use crate::utils::audit_utils::AuditEvent;
+use crate::utils::birthday_utils::{BirthdayStats, CelebrantDetails};
+use crate::utils::cake_day_utils::{CakeDay, YearSummary};
+use crate::utils::color_role_utils::{OrphanReason, OrphanedRole};
+use crate::utils::color_utils::{ContrastReport, Rgb};
+use crate::utils::date_utils::{birthstone, chinese_zodiac, days_until_next_birthday, format_date_without_year, format_weekday, western_zodiac};
+use chrono::{Datelike, Month, NaiveDate, Utc, Weekday};
 use poise::serenity_prelude::{ChannelId, Color, CreateEmbed, CreateEmbedFooter, Mentionable, UserId};
 
-pub fn create_birthday_embed(user_mentions: String) -> CreateEmbed {
+/// How many fellow celebrants a birthday embed names before summarizing the rest.
+const MAX_LISTED_CELEBRANTS: usize = 10;
+
+/// Mentions the first few users and counts the rest, keeping the field well within Discord's 1024 characters.
+fn list_mentions(user_ids: &[i64], max_listed: usize) -> String {
+  let listed = user_ids
+      .iter()
+      .take(max_listed)
+      .map(|user_id| format!("<@{}>", user_id))
+      .collect::<Vec<String>>()
+      .join(", ");
+
+  match user_ids.len().saturating_sub(max_listed) {
+    0 => listed,
+    remaining => format!("{} and {} more", listed, remaining),
+  }
+}
+
+pub fn create_birthday_embed(details: &CelebrantDetails) -> CreateEmbed {
+  let mentions = |user_ids: &[i64]| list_mentions(user_ids, MAX_LISTED_CELEBRANTS);
+
+  let mut fields = Vec::new();
+  if let Some(milestone) = details.milestone {
+    fields.push(("🏆 Milestone:", milestone.to_string(), false));
+  }
+  if !details.born_same_day.is_empty() {
+    fields.push(("👯 Born the very same day as:", mentions(&details.born_same_day), false));
+  } else if !details.shares_with.is_empty() {
+    fields.push(("🎈 Sharing the day with:", mentions(&details.shares_with), false));
+  }
+
   CreateEmbed::new()
       .title("🎉 **Happy Birthday**!")
+      .description(format!("<@{}> turns **{}** today!", details.user_id, details.age))
+      .color(if details.milestone.is_some() { Color::ORANGE } else { Color::GOLD })
+      .fields(fields)
+      .footer(CreateEmbedFooter::new("Don't forget to set your or your friends' birthdays!"))
+}
+
+/// Embed descriptions are capped at 4096 characters; the rest leaves room for the title and footer
+/// within the 6000 characters a message may hold across its embeds.
+pub const EMBED_DESCRIPTION_LIMIT: usize = 4000;
+
+/// Joins the lines into as few newline-separated chunks of at most `max_chars` characters as possible.
+///
+/// A single line longer than `max_chars` is cut short with an ellipsis.
+pub fn chunk_lines(lines: &[String], max_chars: usize) -> Vec<String> {
+  let mut chunks: Vec<String> = Vec::new();
+  let mut current = String::new();
+
+  for line in lines {
+    let line = if line.chars().count() > max_chars {
+      format!("{}…", line.chars().take(max_chars.saturating_sub(1)).collect::<String>())
+    } else {
+      line.clone()
+    };
+
+    if !current.is_empty() && current.chars().count() + 1 + line.chars().count() > max_chars {
+      chunks.push(std::mem::take(&mut current));
+    }
+
+    if !current.is_empty() {
+      current.push('\n');
+    }
+    current.push_str(&line);
+  }
+
+  if !current.is_empty() {
+    chunks.push(current);
+  }
+
+  chunks
+}
+
+pub fn create_anniversary_embed(lines: String) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎊 **Happy Server Anniversary**!")
+      .color(Color::BLUE)
+      .description(format!("📅 **Anniversaries Today:**\n{}", lines))
+      .footer(CreateEmbedFooter::new("Don't want to be announced? Use /anniversary optout."))
+}
+
+pub fn create_custom_event_embed(events: &[CustomEvent]) -> CreateEmbed {
+  let names = events
+      .iter()
+      .map(|event| format!("**{}**", event.name))
+      .collect::<Vec<String>>()
+      .join("\n");
+
+  CreateEmbed::new()
+      .title("📣 **Happening Today**!")
+      .color(Color::PURPLE)
+      .fields(vec![
+        ("📅 Events:", names, false),
+      ])
+      .footer(CreateEmbedFooter::new("See all upcoming events with /event list."))
+}
+
+pub fn create_custom_event_created_embed(name: &str, recurrence: &str, next: String) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📣 Event Created!")
+      .description(format!("**{}** repeats {} and is next on **{}**.", name, recurrence.to_lowercase(), next))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("It will be announced in the announcement channel."))
+}
+
+pub fn create_custom_event_deleted_embed(event_id: i32) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🗑 Event Deleted!")
+      .description(format!("Event `#{}` won't be announced anymore.", event_id))
+      .color(Color::DARK_GREEN)
+}
+
+pub fn create_empty_custom_events_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📣 No Events")
+      .description("This server has no custom events yet.")
+      .color(Color::BLUE)
+      .footer(CreateEmbedFooter::new("Moderators can add one with /event create."))
+}
+
+pub fn create_cake_day_embed(cake_day: CakeDay, years: i32, summary: &YearSummary) -> CreateEmbed {
+  let (title, description) = match cake_day {
+    CakeDay::Guild => ("🍰 **Happy Cake Day**!", format!("This server turns **{}** today!", years)),
+    CakeDay::Bot => ("🍰 **Happy Cake Day**!", format!("I joined this server **{}** year(s) ago today!", years)),
+  };
+
+  CreateEmbed::new()
+      .title(title)
+      .description(description)
       .color(Color::GOLD)
       .fields(vec![
-        ("📅 Birthdays Today:", user_mentions, false),
+        ("🎂 Birthdays celebrated:", summary.birthdays_celebrated.to_string(), true),
+        ("📝 Birthday changes:", summary.birthday_changes.to_string(), true),
+        ("🎨 Colors assigned:", summary.colors_assigned.to_string(), true),
       ])
-      .footer(CreateEmbedFooter::new("Don't forget to set your or your friends' birthdays!"))
+      .footer(CreateEmbedFooter::new("Here's to another year together!"))
+}
+
+pub fn create_birthday_calendar_embed(upcoming: String) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📅 Upcoming Birthdays")
+      .description(upcoming)
+      .color(Color::GOLD)
+      .footer(CreateEmbedFooter::new("Updated daily and whenever a birthday changes."))
+      .timestamp(Utc::now())
+}
+
+pub fn create_birthday_wishes_embed(guild_name: &str, wishes: &[BirthdayWish]) -> CreateEmbed {
+  let mut description = String::new();
+
+  for wish in wishes {
+    let entry = format!("**From <@{}>:**\n> {}\n\n", wish.author_id, wish.message.replace('\n', "\n> "));
+
+    // Embed descriptions are limited to 4096 characters.
+    if description.chars().count() + entry.chars().count() > 4000 {
+      description.push_str("…and more wishes that didn't fit!");
+      break;
+    }
+    description.push_str(&entry);
+  }
+
+  CreateEmbed::new()
+      .title(format!("💌 Birthday Wishes from {}", guild_name))
+      .description(description)
+      .color(Color::FABLED_PINK)
+      .footer(CreateEmbedFooter::new(format!("{} people wished you a happy birthday!", wishes.len())))
+}
+
+pub fn create_birthday_wish_saved_embed(user_id: UserId, updated: bool, days_until: i64) -> CreateEmbed {
+  CreateEmbed::new()
+      .title(if updated { "💌 Wish Updated!" } else { "💌 Wish Saved!" })
+      .description(format!("Your wish for <@{}> will be delivered on their birthday in **{}** days.", user_id, days_until))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("You can edit it with /birthday wish or remove it with /birthday unwish."))
+}
+
+pub fn create_birthday_wish_deleted_embed(user_id: UserId) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("💌 Wish Removed")
+      .description(format!("Your wish for <@{}> has been removed.", user_id))
+      .color(Color::DARK_GREEN)
 }
 
-pub fn create_birthday_info_embed(formatted_birthday: String, days_until: i64) -> CreateEmbed {
+/// The zodiac, birthstone and weekday fields are only added when `extras` has the birth date.
+pub fn create_birthday_info_embed(formatted_birthday: String, days_until: i64, extras: Option<NaiveDate>) -> CreateEmbed {
+  let mut fields = vec![
+    ("🎉 Birthday:", formatted_birthday, false),
+    ("📅 Next Celebration:", format!("In {} days!", days_until), false),
+  ];
+
+  if let Some(date) = extras {
+    fields.push(("✨ Zodiac Sign:", western_zodiac(date).to_string(), true));
+    if let Some(animal) = chinese_zodiac(date) {
+      fields.push(("🏮 Chinese Zodiac:", animal.to_string(), true));
+    }
+    fields.extend([
+      ("💎 Birthstone:", birthstone(date.month()).to_string(), true),
+      ("🗓 Born On A:", format_weekday(date), true),
+    ]);
+  }
+
   CreateEmbed::new()
       .title("🎂 Birthday Information")
       .description("Here's the birthday info you requested!")
       .color(Color::GOLD)
-      .fields(vec![
-        ("🎉 Birthday:", formatted_birthday, false),
-        ("📅 Next Celebration:", format!("In {} days!", days_until), false),
-      ])
+      .fields(fields)
       .footer(CreateEmbedFooter::new("We're excited for the upcoming celebration!"))
 }
 
@@ -30,6 +226,36 @@ pub fn create_birthday_set_embed(user_id: i64, date: String) -> CreateEmbed {
       .footer(CreateEmbedFooter::new("We're excited for the celebration!"))
 }
 
+pub fn create_birthday_pending_embed(user_id: i64, date: String) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("⏳ Birthday Awaiting Approval")
+      .description(format!("Birthday for <@{}> (**{}**) has been sent to the moderators for approval.", user_id, date))
+      .color(Color::ORANGE)
+      .footer(CreateEmbedFooter::new("It will show up once a moderator approves it."))
+}
+
+pub fn create_birthday_approval_embed(user_id: i64, formatted_birthday: String) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📝 Birthday Approval Request")
+      .description(format!("<@{}> wants to set their birthday to **{}**.", user_id, formatted_birthday))
+      .color(Color::ORANGE)
+      .footer(CreateEmbedFooter::new("Approve or reject the request below."))
+}
+
+pub fn create_birthday_reviewed_embed(user_id: i64, formatted_birthday: String, approved: bool, moderator: UserId) -> CreateEmbed {
+  let (title, color) = if approved {
+    ("✅ Birthday Approved", Color::DARK_GREEN)
+  } else {
+    ("🚫 Birthday Rejected", Color::RED)
+  };
+
+  CreateEmbed::new()
+      .title(title)
+      .description(format!("Birthday for <@{}> (**{}**) was {} by <@{}>.",
+        user_id, formatted_birthday, if approved { "approved" } else { "rejected" }, moderator))
+      .color(color)
+}
+
 pub fn create_birthday_delete_embed(user_id: i64) -> CreateEmbed {
   CreateEmbed::new()
       .title("🎉 Birthday Deleted Successfully!")
@@ -38,6 +264,29 @@ pub fn create_birthday_delete_embed(user_id: i64) -> CreateEmbed {
       .footer(CreateEmbedFooter::new("You can always set your birthday again!"))
 }
 
+pub fn create_birthday_delete_confirm_embed(user_id: i64) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("⚠ Delete Birthday?")
+      .description(format!("Are you sure you want to delete the birthday for <@{}>?", user_id))
+      .color(Color::ORANGE)
+      .footer(CreateEmbedFooter::new("This request expires in a minute."))
+}
+
+pub fn create_birthday_delete_cancelled_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("✋ Deletion Cancelled")
+      .description("The birthday was not deleted.")
+      .color(Color::LIGHT_GREY)
+}
+
+pub fn create_birthday_restored_embed(user_id: i64) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("↩ Birthday Restored!")
+      .description(format!("Birthday for <@{}> has been restored.", user_id))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("Nothing was lost!"))
+}
+
 pub fn create_error_embed(description: String, footer: String) -> CreateEmbed {
   CreateEmbed::new()
       .title("❗ Error Occured")
@@ -54,6 +303,38 @@ pub fn create_settings_embed(channel: ChannelId) -> CreateEmbed {
       .footer(CreateEmbedFooter::new("Announcments going to be sent there!"))
 }
 
+pub fn create_board_settings_embed(channel: ChannelId) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎂 Birthday Board Posted!")
+      .description(format!("Birthday board has been posted to **{}**.", channel.mention()))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("It will be kept up to date automatically!"))
+}
+
+pub fn create_calendar_settings_embed(channel: ChannelId, size: i32) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📅 Birthday Calendar Set!")
+      .description(format!("Birthday calendar showing the next **{}** birthdays will be kept in **{}**.", size, channel.mention()))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("It will be updated every day!"))
+}
+
+pub fn create_settings_updated_embed(description: String) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("⚙ Settings Updated!")
+      .description(description)
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("You can change this at any time with /settings."))
+}
+
+pub fn create_birthday_month_embed(month_name: String, count: usize, attachment: &str) -> CreateEmbed {
+  CreateEmbed::new()
+      .title(format!("🎂 Birthdays in {}", month_name))
+      .description(format!("**{}** birthdays this month.", count))
+      .image(format!("attachment://{}", attachment))
+      .color(Color::GOLD)
+}
+
 pub fn create_empty_birthday_embed() -> CreateEmbed {
   CreateEmbed::new()
       .title("🎉 No Birthdays Set!")
@@ -62,24 +343,339 @@ pub fn create_empty_birthday_embed() -> CreateEmbed {
       .footer(CreateEmbedFooter::new("Set your birthdays and make the guild special!"))
 }
 
-pub fn create_color_updated_embed(hex: String, r: u8, g: u8, b: u8, user_id: UserId) -> CreateEmbed {
+pub fn create_color_updated_embed(color: Rgb, user_id: UserId, contrast: ContrastReport, note: Option<String>) -> CreateEmbed {
   CreateEmbed::new()
       .title("🎨 Color Updated!")
       .description(format!(
         "The role color for <@{}> has been updated to **{}**.",
-        user_id, hex
+        user_id, color
       ))
-      .color(Color::from_rgb(r, g, b))
+      .color(color)
+      .fields(color_contrast_fields(contrast, note))
       .footer(CreateEmbedFooter::new("Enjoy your new color!"))
 }
 
-pub fn create_color_created_embed(hex: String, r: u8, g: u8, b: u8, user_id: UserId) -> CreateEmbed {
+pub fn create_color_created_embed(color: Rgb, user_id: UserId, contrast: ContrastReport, note: Option<String>) -> CreateEmbed {
   CreateEmbed::new()
       .title("🎨 New Role Created!")
       .description(format!(
         "A new role with color **{}** has been created and assigned to <@{}>.",
-        hex, user_id
+        color, user_id
       ))
-      .color(Color::from_rgb(r, g, b))
+      .color(color)
+      .fields(color_contrast_fields(contrast, note))
       .footer(CreateEmbedFooter::new("Enjoy your new role!"))
 }
+
+pub fn create_color_cleanup_confirm_embed(orphans: &[OrphanedRole]) -> CreateEmbed {
+  let mut description = String::new();
+
+  for orphan in orphans {
+    let reason = match orphan.reason {
+      OrphanReason::UserLeft => "member left",
+      OrphanReason::NoHolder => "nobody has it",
+    };
+    let entry = format!("<@&{}> · <@{}> ({})\n", orphan.role_id, orphan.user_id, reason);
+
+    // Embed descriptions are limited to 4096 characters.
+    if description.chars().count() + entry.chars().count() > 4000 {
+      description.push_str("…and more.");
+      break;
+    }
+    description.push_str(&entry);
+  }
+
+  CreateEmbed::new()
+      .title(format!("🧹 {} Orphaned Color Roles", orphans.len()))
+      .description(description)
+      .color(Color::ORANGE)
+      .footer(CreateEmbedFooter::new("Delete them? This can't be undone."))
+}
+
+pub fn create_color_cleanup_done_embed(deleted: usize, total: usize) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🧹 Color Roles Cleaned Up!")
+      .description(format!("Deleted **{}** of **{}** orphaned color roles.", deleted, total))
+      .color(Color::DARK_GREEN)
+}
+
+pub fn create_color_cleanup_cancelled_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🧹 Cleanup Cancelled")
+      .description("No roles were deleted.")
+      .color(Color::LIGHT_GREY)
+}
+
+pub fn create_no_orphaned_roles_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🧹 Nothing to Clean Up")
+      .description("Every color role still belongs to a member.")
+      .color(Color::DARK_GREEN)
+}
+
+pub fn create_palette_color_added_embed(name: &str, color: Rgb) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎨 Palette Color Saved!")
+      .description(format!("**{}** ({}) is now part of the palette.", name, color))
+      .color(color)
+      .footer(CreateEmbedFooter::new("Members can pick it with /color set."))
+}
+
+pub fn create_palette_color_removed_embed(name: &str) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🗑 Palette Color Removed!")
+      .description(format!("**{}** has been removed from the palette.", name))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("Members who already use it keep their color."))
+}
+
+pub fn create_empty_palette_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎨 Palette Empty")
+      .description("This server has no palette colors yet.")
+      .color(Color::BLUE)
+      .footer(CreateEmbedFooter::new("Admins can add one with /colorpalette add."))
+}
+
+pub fn create_palette_picker_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎨 Pick a Color")
+      .description("Choose a color from the server palette below.")
+      .color(Color::BLUE)
+      .footer(CreateEmbedFooter::new("The menu expires after a minute."))
+}
+
+pub fn create_avatar_colors_embed(user_id: UserId, colors: &[Rgb]) -> CreateEmbed {
+  let description = colors
+      .iter()
+      .enumerate()
+      .map(|(index, color)| format!("{}. **{}**", index + 1, color))
+      .collect::<Vec<String>>()
+      .join("\n");
+
+  let mut embed = CreateEmbed::new()
+      .title("🖼 Colors From Avatar")
+      .description(format!("The most common colors in <@{}>'s avatar:\n{}", user_id, description))
+      .footer(CreateEmbedFooter::new("Pick one below within a minute to apply it."));
+
+  if let Some(color) = colors.first() {
+    embed = embed.color(*color);
+  }
+
+  embed
+}
+
+pub fn create_color_preview_embed(color: Rgb, user_id: UserId, contrast: ContrastReport, note: Option<String>, can_apply: bool, attachment: &str) -> CreateEmbed {
+  let footer = if can_apply {
+    "Press Apply within a minute to use it."
+  } else {
+    "This color can't be used on this server."
+  };
+
+  CreateEmbed::new()
+      .title("🎨 Color Preview")
+      .description(format!("This is how **{}** looks for <@{}> on Discord's themes.", color, user_id))
+      .image(format!("attachment://{}", attachment))
+      .color(color)
+      .fields(color_contrast_fields(contrast, note))
+      .footer(CreateEmbedFooter::new(footer))
+}
+
+pub fn create_color_reset_embed(old_color: Rgb, user_id: UserId) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎨 Color Removed")
+      .description(format!(
+        "<@{}> no longer has a custom color (it was **{}**).",
+        user_id, old_color
+      ))
+      .color(old_color)
+      .footer(CreateEmbedFooter::new("Pick a new one any time with /color set."))
+}
+
+/// One page of `/color history`, tinted with the color itself.
+pub fn create_color_history_embed(color: Rgb, changed_at: i64, steps_back: usize) -> CreateEmbed {
+  let description = if steps_back == 0 {
+    format!("**{}** (latest)\nSet <t:{}:R>", color, changed_at)
+  } else {
+    format!("**{}**\nSet <t:{}:R>\n\nGo back to it with `/color undo steps: {}`.", color, changed_at, steps_back)
+  };
+
+  CreateEmbed::new()
+      .title("🎨 Color History")
+      .description(description)
+      .color(color)
+}
+
+pub fn create_empty_color_history_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎨 Color History Empty")
+      .description("You haven't changed your color here yet.")
+      .color(Color::BLUE)
+      .footer(CreateEmbedFooter::new("Pick one with /color set."))
+}
+
+fn color_contrast_fields(contrast: ContrastReport, note: Option<String>) -> Vec<(&'static str, String, bool)> {
+  let mut fields = vec![
+    ("🌙 Dark theme contrast:", format!("{:.2}:1", contrast.dark), true),
+    ("☀ Light theme contrast:", format!("{:.2}:1", contrast.light), true),
+  ];
+
+  if let Some(note) = note {
+    fields.push(("👀 Readability:", note, false));
+  }
+
+  fields
+}
+
+pub fn create_birthday_board_embed(page: &str, current_page: usize, total_pages: usize) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎂 Birthday Board")
+      .description(page)
+      .color(Color::GOLD)
+      .footer(CreateEmbedFooter::new(format!("Page {} of {}", current_page + 1, total_pages)))
+      .timestamp(Utc::now())
+}
+
+const STATS_BAR_WIDTH: i64 = 20;
+
+pub fn create_birthday_stats_embed(stats: &BirthdayStats) -> CreateEmbed {
+  let registered = match stats.members {
+    Some(members) => format!("{} of {} members", stats.registered, members),
+    None => stats.registered.to_string(),
+  };
+
+  let busiest_month = stats.by_month
+      .first()
+      .and_then(|entry| Month::try_from(entry.key as u8).ok().map(|month| (month, entry.count)))
+      .map(|(month, count)| format!("{} ({} birthdays)", month.name(), count))
+      .unwrap_or_else(|| "-".to_string());
+
+  // Bars are scaled to the busiest weekday so they always fit in the field.
+  let busiest_weekday = stats.by_weekday.iter().map(|entry| entry.count).max().unwrap_or(0).max(1);
+  let by_weekday = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun]
+      .iter()
+      .map(|weekday| {
+        let count = stats.by_weekday
+            .iter()
+            .find(|entry| entry.key as u32 == weekday.num_days_from_sunday())
+            .map(|entry| entry.count)
+            .unwrap_or(0);
+
+        let bar = (count * STATS_BAR_WIDTH + busiest_weekday - 1) / busiest_weekday;
+        format!("`{}` {} {}\n", weekday, "▰".repeat(bar as usize), count)
+      })
+      .collect::<String>();
+
+  let average_age = stats.average_age
+      .map(|age| format!("{:.1} years", age))
+      .unwrap_or_else(|| "-".to_string());
+
+  let next_birthday = stats.next_birthday
+      .as_ref()
+      .map(|birthday| format!(
+        "<@{}> on {} (in {} days)",
+        birthday.user_id,
+        format_date_without_year(birthday.date),
+        days_until_next_birthday(birthday.date)
+      ))
+      .unwrap_or_else(|| "-".to_string());
+
+  CreateEmbed::new()
+      .title("📊 Birthday Statistics")
+      .color(Color::GOLD)
+      .fields(vec![
+        ("🎂 Registered:", registered, true),
+        ("📅 Busiest Month:", busiest_month, true),
+        ("🧮 Average Age:", average_age, true),
+        ("🎉 Next Birthday:", next_birthday, false),
+        ("🗓 Weekdays This Year:", by_weekday, false),
+      ])
+      .footer(CreateEmbedFooter::new("Set your birthday with /birthday set!"))
+}
+
+pub fn create_audit_embed(event: &AuditEvent) -> CreateEmbed {
+  CreateEmbed::new()
+      .title(event.action.label())
+      .color(Color::LIGHT_GREY)
+      .fields(vec![
+        ("Actor:", format!("<@{}>", event.actor_id), true),
+        ("Target:", format!("<@{}>", event.target_id), true),
+        ("Old Value:", event.old_value.clone().unwrap_or_else(|| "-".to_string()), false),
+        ("New Value:", event.new_value.clone().unwrap_or_else(|| "-".to_string()), false),
+      ])
+      .timestamp(Utc::now())
+}
+
+pub fn create_audit_settings_embed(channel: ChannelId) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📜 Audit Log Channel Set!")
+      .description(format!("Audit log channel has been set to **{}**.", channel.mention()))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("Birthday and color changes are going to be logged there!"))
+}
+
+pub fn create_anniversary_settings_embed(channel: ChannelId) -> CreateEmbed {
+  CreateEmbed::new()
+      .title("🎊 Anniversary Channel Set!")
+      .description(format!("Anniversary channel has been set to **{}**.", channel.mention()))
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("Server join anniversaries are going to be announced there!"))
+}
+
+pub fn create_anniversary_opt_embed(opted_out: bool) -> CreateEmbed {
+  let description = if opted_out {
+    "Your server join anniversaries won't be announced anymore."
+  } else {
+    "Your server join anniversaries will be announced again."
+  };
+
+  CreateEmbed::new()
+      .title("🎊 Anniversary Preference Saved!")
+      .description(description)
+      .color(Color::DARK_GREEN)
+      .footer(CreateEmbedFooter::new("You can change this at any time with /anniversary."))
+}
+
+pub fn create_empty_audit_embed() -> CreateEmbed {
+  CreateEmbed::new()
+      .title("📜 Audit Log Empty")
+      .description("No birthday or color changes have been recorded yet.")
+      .color(Color::ORANGE)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{chunk_lines, list_mentions};
+
+  #[test]
+  fn chunk_lines_keeps_short_input_together() {
+    let lines = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(chunk_lines(&lines, 10), vec!["a\nb".to_string()]);
+  }
+
+  #[test]
+  fn chunk_lines_splits_between_lines() {
+    let lines = vec!["aaaa".to_string(); 5];
+    let chunks = chunk_lines(&lines, 9);
+
+    assert_eq!(chunks, vec!["aaaa\naaaa", "aaaa\naaaa", "aaaa"]);
+    assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 9));
+  }
+
+  #[test]
+  fn chunk_lines_truncates_overlong_lines() {
+    let lines = vec!["a".repeat(20)];
+    assert_eq!(chunk_lines(&lines, 5), vec!["aaaa…".to_string()]);
+  }
+
+  #[test]
+  fn chunk_lines_handles_no_lines() {
+    assert!(chunk_lines(&[], 10).is_empty());
+  }
+
+  #[test]
+  fn list_mentions_summarizes_the_rest() {
+    assert_eq!(list_mentions(&[1, 2], 3), "<@1>, <@2>");
+    assert_eq!(list_mentions(&[1, 2, 3, 4, 5], 3), "<@1>, <@2>, <@3> and 2 more");
+  }
+}
@@ -0,0 +1,175 @@
+use crate::db::models::CustomEvent;
+use crate::db::queries::{get_announcement_channel, list_unannounced_custom_events, mark_custom_events_announced};
+use crate::utils::birthday_utils::group_by_guild;
+use crate::utils::date_utils::{days_in_month, format_date};
+use crate::utils::embed_utils::create_custom_event_embed;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::{ChannelId, CreateAllowedMentions, CreateMessage, Http, RoleId};
+use poise::ChoiceParameter;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const EVENT_LIST_PAGE_SIZE: usize = 10;
+
+/// How often a custom event comes back after its first date.
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum Recurrence {
+  Yearly,
+  Monthly,
+  Weekly,
+}
+
+impl Recurrence {
+  /// Whether the event falls on `day`. Dates past the end of a shorter month move to its last day.
+  pub fn occurs_on(&self, start: NaiveDate, day: NaiveDate) -> bool {
+    if day < start {
+      return false;
+    }
+
+    match self {
+      Recurrence::Yearly => {
+        day.month() == start.month() && day.day() == start.day().min(days_in_month(day.year(), day.month()))
+      }
+      Recurrence::Monthly => day.day() == start.day().min(days_in_month(day.year(), day.month())),
+      Recurrence::Weekly => day.weekday() == start.weekday(),
+    }
+  }
+
+  pub fn next_occurrence(&self, start: NaiveDate, today: NaiveDate) -> NaiveDate {
+    let from = start.max(today);
+
+    (0..=366)
+        .map(|offset| from + Duration::days(offset))
+        .find(|day| self.occurs_on(start, *day))
+        .unwrap_or(from)
+  }
+}
+
+pub fn event_recurrence(event: &CustomEvent) -> Recurrence {
+  Recurrence::from_name(&event.recurrence).unwrap_or(Recurrence::Yearly)
+}
+
+pub fn format_custom_event(event: &CustomEvent, today: NaiveDate) -> String {
+  let recurrence = event_recurrence(event);
+  let next = recurrence.next_occurrence(event.date, today);
+  let role = event.role_id.map(|role_id| format!(" · pings <@&{}>", role_id)).unwrap_or_default();
+
+  format!(
+    "`#{}` **{}** · {} · next on {}{}\n",
+    event.id,
+    event.name,
+    recurrence.name(),
+    format_date(next),
+    role,
+  )
+}
+
+pub fn create_custom_event_pages(events: &[CustomEvent]) -> Vec<String> {
+  let today = Local::now().naive_utc().date();
+
+  events
+      .chunks(EVENT_LIST_PAGE_SIZE)
+      .map(|chunk| chunk.iter().map(|event| format_custom_event(event, today)).collect::<String>())
+      .collect()
+}
+
+pub async fn handle_custom_event_announcements(http: &Arc<Http>, db_pool: Arc<Mutex<SqliteConnection>>) -> Result<(), Box<dyn std::error::Error>> {
+  let mut conn = db_pool.lock().await;
+  let today = Local::now().naive_utc().date();
+
+  let events = list_unannounced_custom_events(&mut conn, today)?
+      .into_iter()
+      .filter(|event| event_recurrence(event).occurs_on(event.date, today))
+      .collect::<Vec<CustomEvent>>();
+  if events.is_empty() {
+    return Ok(());
+  }
+
+  for (guild_id, guild_events) in group_by_guild(events, |event| event.guild_id) {
+    if let Err(e) = announce_custom_events_to_guild(http, &mut conn, guild_id, &guild_events).await {
+      error!("Error announcing custom events for guild {}: {:?}", guild_id, e);
+      continue;
+    }
+
+    let event_ids = guild_events.iter().map(|event| event.id).collect::<Vec<i32>>();
+    if let Err(e) = mark_custom_events_announced(&mut conn, event_ids, today) {
+      error!("Error marking custom events announced for guild {}: {:?}", guild_id, e);
+    }
+  }
+
+  Ok(())
+}
+
+async fn announce_custom_events_to_guild(
+  http: &Http,
+  conn: &mut SqliteConnection,
+  guild_id: i64,
+  events: &[CustomEvent],
+) -> Result<(), Box<dyn std::error::Error>> {
+  let Some(channel_id) = get_announcement_channel(conn, guild_id)? else {
+    return Err(format!("Announcement channel not set for guild {}", guild_id).into());
+  };
+
+  let mut roles = events.iter().filter_map(|event| event.role_id).collect::<Vec<i64>>();
+  roles.sort_unstable();
+  roles.dedup();
+
+  let mut message = CreateMessage::new().embed(create_custom_event_embed(events));
+  if !roles.is_empty() {
+    let content = roles.iter().map(|role_id| format!("<@&{}>", role_id)).collect::<Vec<String>>().join(" ");
+
+    message = message
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new().roles(roles.iter().map(|role_id| RoleId::new(*role_id as u64))));
+  }
+
+  ChannelId::new(channel_id as u64).send_message(http, message).await?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Recurrence;
+  use chrono::NaiveDate;
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+  }
+
+  #[test]
+  fn monthly_on_the_31st_moves_to_the_last_day() {
+    let start = date(2026, 1, 31);
+
+    assert!(Recurrence::Monthly.occurs_on(start, date(2026, 2, 28)));
+    assert!(Recurrence::Monthly.occurs_on(start, date(2026, 4, 30)));
+    assert!(Recurrence::Monthly.occurs_on(start, date(2026, 5, 31)));
+    assert!(!Recurrence::Monthly.occurs_on(start, date(2026, 5, 30)));
+    assert_eq!(Recurrence::Monthly.next_occurrence(start, date(2026, 2, 1)), date(2026, 2, 28));
+    assert_eq!(Recurrence::Monthly.next_occurrence(start, date(2028, 2, 1)), date(2028, 2, 29));
+  }
+
+  #[test]
+  fn yearly_on_leap_day_falls_on_february_28th_in_common_years() {
+    let start = date(2024, 2, 29);
+
+    assert!(Recurrence::Yearly.occurs_on(start, date(2026, 2, 28)));
+    assert!(!Recurrence::Yearly.occurs_on(start, date(2026, 3, 1)));
+    assert!(Recurrence::Yearly.occurs_on(start, date(2028, 2, 29)));
+    assert!(!Recurrence::Yearly.occurs_on(start, date(2028, 2, 28)));
+    assert_eq!(Recurrence::Yearly.next_occurrence(start, date(2026, 3, 1)), date(2027, 2, 28));
+  }
+
+  #[test]
+  fn nothing_happens_before_the_start() {
+    let start = date(2026, 6, 15);
+
+    assert!(!Recurrence::Yearly.occurs_on(start, date(2025, 6, 15)));
+    assert!(!Recurrence::Monthly.occurs_on(start, date(2026, 5, 15)));
+    assert!(!Recurrence::Weekly.occurs_on(start, date(2026, 6, 8)));
+    assert!(Recurrence::Weekly.occurs_on(start, date(2026, 6, 22)));
+    assert_eq!(Recurrence::Weekly.next_occurrence(start, date(2026, 1, 1)), start);
+  }
+}
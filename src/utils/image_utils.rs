@@ -0,0 +1,151 @@
+use crate::utils::color_utils::{Rgb, DISCORD_DARK_BACKGROUND, DISCORD_LIGHT_BACKGROUND};
+use ab_glyph::{point, Font, FontRef, GlyphId, PxScale, ScaleFont};
+use image::{ImageError, ImageFormat, Rgba, RgbaImage};
+use std::io::Cursor;
+
+const SANS: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+const SERIF: &[u8] = include_bytes!("../../assets/fonts/DejaVuSerif.ttf");
+const MONO: &[u8] = include_bytes!("../../assets/fonts/DejaVuSansMono.ttf");
+
+const PREVIEW_MIN_WIDTH: u32 = 320;
+const PREVIEW_MAX_NAME_WIDTH: f32 = 480.0;
+const PREVIEW_ROW_HEIGHT: u32 = 64;
+const PREVIEW_PADDING: u32 = 16;
+const PREVIEW_NAME_SIZE: f32 = 20.0;
+const PREVIEW_MESSAGE_SIZE: f32 = 16.0;
+const PREVIEW_MESSAGE: &str = "This is how your name will look.";
+
+/// Fonts bundled with the bot for rendered images.
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum FontFamily {
+  Sans,
+  Serif,
+  Mono,
+}
+
+impl FontFamily {
+  pub fn font(&self) -> FontRef<'static> {
+    let data = match self {
+      FontFamily::Sans => SANS,
+      FontFamily::Serif => SERIF,
+      FontFamily::Mono => MONO,
+    };
+
+    FontRef::try_from_slice(data).expect("Bundled font is invalid")
+  }
+}
+
+pub fn fill_rect(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+  let x_end = (x + width).min(image.width());
+  let y_end = (y + height).min(image.height());
+
+  for py in y..y_end {
+    for px in x..x_end {
+      image.put_pixel(px, py, color);
+    }
+  }
+}
+
+pub fn text_width(font: &FontRef, size: f32, text: &str) -> f32 {
+  let scaled = font.as_scaled(PxScale::from(size));
+  let mut width = 0.0;
+  let mut last: Option<GlyphId> = None;
+
+  for c in text.chars() {
+    let id = scaled.glyph_id(c);
+    if let Some(prev) = last {
+      width += scaled.kern(prev, id);
+    }
+    width += scaled.h_advance(id);
+    last = Some(id);
+  }
+
+  width
+}
+
+/// Shortens the text with an ellipsis until it fits in `max_width` pixels.
+pub fn truncate_text(font: &FontRef, size: f32, text: &str, max_width: f32) -> String {
+  if text_width(font, size, text) <= max_width {
+    return text.to_string();
+  }
+
+  let mut truncated = text.to_string();
+  while !truncated.is_empty() {
+    truncated.pop();
+    let candidate = format!("{}…", truncated.trim_end());
+    if text_width(font, size, &candidate) <= max_width {
+      return candidate;
+    }
+  }
+
+  String::new()
+}
+
+/// Draws the text with its top-left corner at (`x`, `y`).
+pub fn draw_text(image: &mut RgbaImage, font: &FontRef, size: f32, x: f32, y: f32, text: &str, color: Rgba<u8>) {
+  let scaled = font.as_scaled(PxScale::from(size));
+  let baseline = y + scaled.ascent();
+  let mut caret = x;
+  let mut last: Option<GlyphId> = None;
+
+  for c in text.chars() {
+    let id = scaled.glyph_id(c);
+    if let Some(prev) = last {
+      caret += scaled.kern(prev, id);
+    }
+
+    let glyph = id.with_scale_and_position(scaled.scale(), point(caret, baseline));
+    caret += scaled.h_advance(id);
+    last = Some(id);
+
+    let Some(outlined) = font.outline_glyph(glyph) else {
+      continue;
+    };
+
+    let bounds = outlined.px_bounds();
+    outlined.draw(|gx, gy, coverage| {
+      let px = bounds.min.x as i32 + gx as i32;
+      let py = bounds.min.y as i32 + gy as i32;
+
+      if px < 0 || py < 0 || px >= image.width() as i32 || py >= image.height() as i32 {
+        return;
+      }
+
+      let alpha = coverage * (color[3] as f32 / 255.0);
+      let pixel = image.get_pixel_mut(px as u32, py as u32);
+      for channel in 0..3 {
+        pixel[channel] = (pixel[channel] as f32 * (1.0 - alpha) + color[channel] as f32 * alpha).round() as u8;
+      }
+    });
+  }
+}
+
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, ImageError> {
+  let mut bytes = Cursor::new(Vec::new());
+  image.write_to(&mut bytes, ImageFormat::Png)?;
+
+  Ok(bytes.into_inner())
+}
+
+/// Renders the name in the color above a sample message, once on Discord's dark theme and once on the light one.
+pub fn render_color_preview(name: &str, color: Rgb) -> Result<Vec<u8>, ImageError> {
+  let font = FontFamily::Sans.font();
+  let name = truncate_text(&font, PREVIEW_NAME_SIZE, name, PREVIEW_MAX_NAME_WIDTH);
+  let content_width = text_width(&font, PREVIEW_NAME_SIZE, &name).max(text_width(&font, PREVIEW_MESSAGE_SIZE, PREVIEW_MESSAGE));
+  let width = (content_width.ceil() as u32 + PREVIEW_PADDING * 2).max(PREVIEW_MIN_WIDTH);
+
+  let mut image = RgbaImage::new(width, PREVIEW_ROW_HEIGHT * 2);
+  let themes = [
+    (DISCORD_DARK_BACKGROUND, Rgba([219, 222, 225, 255])),
+    (DISCORD_LIGHT_BACKGROUND, Rgba([49, 51, 56, 255])),
+  ];
+
+  for (row, (background, text)) in themes.into_iter().enumerate() {
+    let y = row as u32 * PREVIEW_ROW_HEIGHT;
+    fill_rect(&mut image, 0, y, width, PREVIEW_ROW_HEIGHT, Rgba([background.r, background.g, background.b, 255]));
+    draw_text(&mut image, &font, PREVIEW_NAME_SIZE, PREVIEW_PADDING as f32, (y + 10) as f32, &name, Rgba([color.r, color.g, color.b, 255]));
+    draw_text(&mut image, &font, PREVIEW_MESSAGE_SIZE, PREVIEW_PADDING as f32, (y + 36) as f32, PREVIEW_MESSAGE, text);
+  }
+
+  encode_png(&image)
+}
@@ -2,4 +2,13 @@ pub mod color_utils;
 pub mod user_utils;
 pub mod date_utils;
 pub mod birthday_utils;
-pub mod embed_utils;
\ No newline at end of file
+pub mod embed_utils;
+pub mod image_utils;
+pub mod calendar_utils;
+pub mod pagination_utils;
+pub mod audit_utils;
+pub mod anniversary_utils;
+pub mod event_utils;
+pub mod cake_day_utils;
+pub mod color_role_utils;
+pub mod avatar_utils;
\ No newline at end of file
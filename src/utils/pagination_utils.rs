@@ -0,0 +1,81 @@
+use crate::{Context, Error};
+use chrono::Utc;
+use poise::serenity_prelude::{Color, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage};
+use poise::CreateReply;
+
+/// Sends the pages as an ephemeral embed the author can flip through with buttons.
+pub async fn paginate(
+  ctx: Context<'_>,
+  title: &str,
+  pages: &[String],
+) -> Result<(), Error> {
+  let embeds = pages
+      .iter()
+      .map(|page| CreateEmbed::new().title(title).description(page).color(Color::BLUE))
+      .collect::<Vec<CreateEmbed>>();
+
+  paginate_embeds(ctx, &embeds).await
+}
+
+/// Like [`paginate`], but every page is its own embed, e.g. to give each page a different color.
+pub async fn paginate_embeds(
+  ctx: Context<'_>,
+  embeds: &[CreateEmbed],
+) -> Result<(), Error> {
+  let ctx_id = ctx.id();
+  let prev_button_id = format!("{}prev", ctx_id);
+  let next_button_id = format!("{}next", ctx_id);
+
+  let mut current_page = 0;
+  let total_pages = embeds.len();
+
+  let page_embed = |page: usize| {
+    embeds[page]
+        .clone()
+        .footer(CreateEmbedFooter::new(format!("Page {} of {}", page + 1, total_pages)))
+        .timestamp(Utc::now())
+  };
+
+  let reply = {
+    let components = CreateActionRow::Buttons(vec![
+      CreateButton::new(&prev_button_id).emoji('◀'),
+      CreateButton::new(&next_button_id).emoji('▶'),
+    ]);
+
+    CreateReply::default()
+        .embed(page_embed(0))
+        .components(vec![components])
+  };
+
+  ctx.send(reply.ephemeral(true)).await?;
+
+  while let Some(press) = ComponentInteractionCollector::new(ctx)
+      .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+      .timeout(std::time::Duration::from_secs(3600 * 24))
+      .await
+  {
+    if press.data.custom_id == next_button_id {
+      current_page += 1;
+      if current_page >= total_pages {
+        current_page = 0;
+      }
+    } else if press.data.custom_id == prev_button_id {
+      current_page = current_page.checked_sub(1).unwrap_or(total_pages - 1);
+    } else {
+      continue;
+    }
+
+    press
+        .create_response(
+          ctx.serenity_context(),
+          CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .embed(page_embed(current_page))
+                .ephemeral(true),
+          ),
+        )
+        .await?;
+  }
+
+  Ok(())
+}
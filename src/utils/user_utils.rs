@@ -1,9 +1,16 @@
+use crate::db::queries::track_personal_color_role;
+use crate::utils::color_utils::Rgb;
 use crate::utils::embed_utils::create_error_embed;
 use crate::{Context, Error};
-use poise::serenity_prelude::{Color, EditRole, Member, Permissions, Role, UserId};
+use diesel::SqliteConnection;
+use log::error;
+use poise::serenity_prelude::json::{json, to_vec};
+use poise::serenity_prelude::{EditRole, Http, LightMethod, Member, Permissions, Request, Role, RoleId, Route, UserId};
 use poise::{serenity_prelude as serenity, CreateReply};
 use serenity::GuildId;
 
+const MEMBERS_PAGE_SIZE: u64 = 1000;
+
 /// Check and get **user specific** role.
 ///
 /// It's a role, which has the same name as user's id.
@@ -36,13 +43,37 @@ pub fn get_user_id(ctx: &Context<'_>, member: Option<&Member>) -> i64 {
   }
 }
 
+/// Returns the user a **user specific** role belongs to, going by its name.
+///
+/// Only snowflake-shaped names count, so roles like "2024" are never taken for one.
+pub fn user_specific_role_owner(role: &Role) -> Option<UserId> {
+  if !(17..=20).contains(&role.name.len()) {
+    return None;
+  }
+
+  role.name
+      .parse::<u64>()
+      .ok()
+      .filter(|id| *id != 0 && id.to_string() == role.name)
+      .map(UserId::new)
+}
+
 /// Create a role for a user with their user id and assign a color.
-pub async fn create_and_assign_user_specific_role(ctx: Context<'_>, guild_id: GuildId, user_id: UserId, r: u8, g: u8, b: u8) -> Result<(), Error> {
+///
+/// The role is tracked right away, so cleanup can find it even if assigning it fails.
+pub async fn create_and_assign_user_specific_role(
+  ctx: Context<'_>,
+  conn: &mut SqliteConnection,
+  guild_id: GuildId,
+  user_id: UserId,
+  color: Rgb,
+  anchor_id: Option<RoleId>,
+) -> Result<(), Error> {
   let role_name = user_id.to_string();
 
   let new_role = EditRole::new()
       .name(role_name)
-      .colour(Color::from_rgb(r, g, b))
+      .colour(color)
       .mentionable(false);
 
   let new_role_id = guild_id
@@ -50,21 +81,165 @@ pub async fn create_and_assign_user_specific_role(ctx: Context<'_>, guild_id: Gu
       .await?
       .id;
 
+  if let Err(e) = track_personal_color_role(conn, i64::from(guild_id), i64::from(user_id), i64::from(new_role_id)) {
+    error!("Error tracking color role {} in guild {}: {:?}", new_role_id, guild_id, e);
+  }
+
   let member = guild_id.member(ctx, user_id).await?;
   member.add_role(ctx, new_role_id).await?;
 
-  let roles = guild_id.roles(ctx).await?;
+  place_color_role(ctx.http(), guild_id, new_role_id, anchor_id).await
+}
+
+/// Pages through the guild's member list, which requires the `GUILD_MEMBERS` intent.
+pub async fn fetch_all_members(http: &Http, guild_id: GuildId) -> Result<Vec<Member>, Error> {
+  let mut members = Vec::new();
+  let mut after: Option<UserId> = None;
+
+  loop {
+    let page = guild_id.members(http, Some(MEMBERS_PAGE_SIZE), after).await?;
+    let page_len = page.len() as u64;
+    after = page.last().map(|member| member.user.id);
+    members.extend(page);
+
+    if page_len < MEMBERS_PAGE_SIZE {
+      break;
+    }
+  }
+
+  Ok(members)
+}
+
+/// Moves the role above every other role so its color shows.
+pub async fn move_role_to_top(http: &Http, guild_id: GuildId, role_id: RoleId) -> Result<(), Error> {
+  let roles = guild_id.roles(http).await?;
   let highest_position = roles
       .iter()
       .map(|(_, role)| role.position)
       .max()
       .unwrap_or(0);
 
-  guild_id.edit_role_position(ctx, new_role_id, highest_position).await?;
+  guild_id.edit_role_position(http, role_id, highest_position).await?;
+
+  Ok(())
+}
+
+/// Puts a new color role right below the anchor role, or at the top when there is no anchor.
+pub async fn place_color_role(http: &Http, guild_id: GuildId, role_id: RoleId, anchor_id: Option<RoleId>) -> Result<(), Error> {
+  match anchor_id {
+    Some(anchor_id) => move_roles_below_anchor(http, guild_id, anchor_id, &[role_id]).await,
+    None => move_role_to_top(http, guild_id, role_id).await,
+  }
+}
+
+/// Moves the roles right below the anchor role in a single request, keeping their order.
+///
+/// Falls back to right below the bot's highest role when the anchor role no longer exists.
+pub async fn move_roles_below_anchor(http: &Http, guild_id: GuildId, anchor_id: RoleId, role_ids: &[RoleId]) -> Result<(), Error> {
+  let roles = guild_id.roles(http).await?;
+  let bot = http.get_current_user_guild_member(guild_id).await?;
+  let bot_top_role = bot
+      .roles
+      .iter()
+      .filter_map(|role_id| roles.get(role_id))
+      .max_by_key(|role| (role.position, role.id));
+
+  let Some(bot_top_role) = bot_top_role else {
+    return Err(Error::Other("I need a role to be able to move roles."));
+  };
+
+  let anchor_id = if roles.contains_key(&anchor_id) { anchor_id } else { bot_top_role.id };
+
+  // Without @everyone, which always stays at position 0.
+  let current: Vec<(RoleId, u16)> = roles
+      .values()
+      .filter(|role| role.id.get() != guild_id.get())
+      .map(|role| (role.id, role.position))
+      .collect();
+
+  let Some(positions) = positions_below_anchor(&current, anchor_id, role_ids) else {
+    return Ok(());
+  };
+
+  // Discord rejects the whole batch if any role in it is at or above the bot's highest role.
+  let out_of_reach = positions.iter().any(|(role_id, position)| {
+    *position >= bot_top_role.position || roles.get(role_id).is_some_and(|role| role.position >= bot_top_role.position)
+  });
+  if out_of_reach {
+    return Err(Error::Other("The color roles and their anchor have to be below my highest role."));
+  }
+
+  if positions.is_empty() {
+    return Ok(());
+  }
+
+  let body: Vec<_> = positions
+      .iter()
+      .map(|(role_id, position)| json!({ "id": role_id, "position": position }))
+      .collect();
+
+  http.request(
+    Request::new(Route::GuildRoles { guild_id }, LightMethod::Patch).body(Some(to_vec(&body)?)),
+  ).await?;
 
   Ok(())
 }
 
+/// Works out the new positions that put `moved` right below the anchor, keeping their order.
+///
+/// Only the roles between the old and the new place of the moved roles shift, and they reuse the
+/// positions that range already had, so roles outside it are left alone. Returns just the roles
+/// whose position changes, or `None` if the anchor isn't among the roles.
+fn positions_below_anchor(roles: &[(RoleId, u16)], anchor_id: RoleId, moved: &[RoleId]) -> Option<Vec<(RoleId, u16)>> {
+  // Lowest first, the way Discord breaks ties.
+  let mut current = roles.to_vec();
+  current.sort_by_key(|(role_id, position)| (*position, *role_id));
+
+  let is_moved = |role_id: &RoleId| *role_id != anchor_id && moved.contains(role_id);
+
+  let (moved_roles, mut reordered): (Vec<_>, Vec<_>) = current
+      .iter()
+      .partition(|(role_id, _)| is_moved(role_id));
+  let anchor_index = reordered.iter().position(|(role_id, _)| *role_id == anchor_id)?;
+  reordered.splice(anchor_index..anchor_index, moved_roles);
+
+  let changed = current
+      .iter()
+      .zip(&reordered)
+      .enumerate()
+      .filter(|(_, (before, after))| before.0 != after.0)
+      .map(|(index, _)| index);
+  let (Some(first), Some(last)) = (changed.clone().min(), changed.max()) else {
+    return Some(Vec::new());
+  };
+
+  // Reuse the range's positions, bumping duplicates so the new order is unambiguous.
+  let mut positions = Vec::new();
+  let mut previous: Option<u16> = None;
+  for (index, (role_id, _)) in reordered.iter().enumerate().take(last + 1).skip(first) {
+    let position = match previous {
+      Some(previous) => current[index].1.max(previous + 1),
+      None => current[index].1,
+    };
+    previous = Some(position);
+
+    let old_position = current.iter().find(|(id, _)| id == role_id).map(|(_, position)| *position);
+    if old_position != Some(position) {
+      positions.push((*role_id, position));
+    }
+  }
+
+  Some(positions)
+}
+
+/// Check whether the command author has the permission in the current channel.
+pub async fn author_has_permission(ctx: &Context<'_>, permission: Permissions) -> bool {
+  ctx.author_member()
+      .await
+      .and_then(|member| member.permissions)
+      .is_some_and(|permissions| permissions.contains(permission))
+}
+
 pub async fn check_permission_for_member(
   ctx: &Context<'_>,
   member: Option<&Member>,
@@ -90,3 +265,75 @@ pub async fn check_permission_for_member(
   }
   Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::positions_below_anchor;
+  use poise::serenity_prelude::RoleId;
+
+  fn role(id: u64) -> RoleId {
+    RoleId::new(id)
+  }
+
+  /// Applies the new positions and lists the role ids from the top down.
+  fn order_after(roles: &[(RoleId, u16)], positions: &[(RoleId, u16)]) -> Vec<u64> {
+    let mut roles = roles
+        .iter()
+        .map(|(role_id, position)| {
+          let position = positions.iter().find(|(id, _)| id == role_id).map_or(*position, |(_, position)| *position);
+          (position, *role_id)
+        })
+        .collect::<Vec<(u16, RoleId)>>();
+    roles.sort();
+
+    roles.iter().rev().map(|(_, role_id)| role_id.get()).collect()
+  }
+
+  #[test]
+  fn moves_roles_up_below_the_anchor() {
+    let roles = [(role(1), 1), (role(2), 2), (role(3), 3), (role(4), 4), (role(5), 5)];
+    let positions = positions_below_anchor(&roles, role(4), &[role(1)]).unwrap();
+
+    assert_eq!(order_after(&roles, &positions), vec![5, 4, 1, 3, 2]);
+    // Role 5 is above the anchor, so it isn't part of the batch.
+    assert!(positions.iter().all(|(role_id, _)| *role_id != role(5)));
+  }
+
+  #[test]
+  fn moves_roles_down_below_the_anchor() {
+    let roles = [(role(1), 1), (role(2), 2), (role(3), 3), (role(4), 4), (role(5), 5)];
+    let positions = positions_below_anchor(&roles, role(2), &[role(5), role(4)]).unwrap();
+
+    assert_eq!(order_after(&roles, &positions), vec![3, 2, 5, 4, 1]);
+    assert!(positions.iter().all(|(role_id, _)| *role_id != role(1)));
+  }
+
+  #[test]
+  fn leaves_roles_already_in_place_alone() {
+    let roles = [(role(1), 1), (role(2), 2), (role(3), 3)];
+    assert_eq!(positions_below_anchor(&roles, role(3), &[role(2)]), Some(Vec::new()));
+  }
+
+  #[test]
+  fn keeps_positions_outside_the_range() {
+    // Gaps and duplicates outside the range stay exactly as they are.
+    let roles = [(role(1), 1), (role(2), 1), (role(3), 5), (role(4), 9), (role(5), 12), (role(6), 12)];
+    let positions = positions_below_anchor(&roles, role(5), &[role(3)]).unwrap();
+
+    assert_eq!(positions, vec![(role(4), 5), (role(3), 9)]);
+  }
+
+  #[test]
+  fn bumps_duplicate_positions_in_the_range() {
+    let roles = [(role(1), 1), (role(2), 2), (role(3), 2), (role(4), 3)];
+    let positions = positions_below_anchor(&roles, role(4), &[role(1)]).unwrap();
+
+    assert_eq!(order_after(&roles, &positions), vec![4, 1, 3, 2]);
+  }
+
+  #[test]
+  fn needs_the_anchor() {
+    let roles = [(role(1), 1), (role(2), 2)];
+    assert_eq!(positions_below_anchor(&roles, role(9), &[role(1)]), None);
+  }
+}